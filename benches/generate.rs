@@ -0,0 +1,28 @@
+//! Times full map generation at a few sizes, run with `cargo bench --bench generate`.
+use std::time::{Duration, Instant};
+use whittaker_map_generator::generator::Generator;
+
+const SIZES: [u32; 3] = [256, 512, 1024];
+const RUNS: u32 = 3;
+
+fn main() {
+    for size in SIZES {
+        let mut fastest = Duration::MAX;
+
+        for _ in 0..RUNS {
+            let mut generator = Generator::builder()
+                .seed("benchmark")
+                .map_size(size)
+                .steppers(size)
+                .steps(size)
+                .build()
+                .expect("the benchmark config is valid");
+
+            let start = Instant::now();
+            generator.generate().expect("the benchmark map generates");
+            fastest = fastest.min(start.elapsed());
+        }
+
+        println!("generate {0}x{0}: {1:.3}s (fastest of {2})", size, fastest.as_secs_f64(), RUNS);
+    }
+}
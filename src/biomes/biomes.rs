@@ -1,6 +1,7 @@
 use image::Rgb;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Biomes {
     Void,
     Placeholder,
@@ -22,8 +23,12 @@ pub enum Biomes {
     Bare,
     Tundra,
     Snow,
+    /// a biome registered at runtime, see [`BiomeTable::register`](crate::biomes::table::BiomeTable::register)
+    Custom(u16),
 }
 
+/// The whittaker diagram, rows go from the hottest to the coldest temperature band and columns from the driest
+/// to the wettest moisture band.
 pub const WHITTAKER: [[Biomes; 6]; 4] = [
     [
         Biomes::SubtropicalDesert,
@@ -60,7 +65,47 @@ pub const WHITTAKER: [[Biomes; 6]; 4] = [
 ];
 
 impl Biomes {
-    pub fn get_symbol(&self) -> &str {
+    /// Every built-in biome, in the order of their ids.
+    pub const ALL: [Biomes; 19] = [
+        Biomes::Void,
+        Biomes::Placeholder,
+        Biomes::FreshWater,
+        Biomes::SaltWater,
+        Biomes::Land,
+        Biomes::Beach,
+        Biomes::SubtropicalDesert,
+        Biomes::Grassland,
+        Biomes::TropicalSeasonalForest,
+        Biomes::TropicalRainForest,
+        Biomes::TemperateDesert,
+        Biomes::TemperateDeciduousForest,
+        Biomes::TemperateRainForest,
+        Biomes::Shrubland,
+        Biomes::Taiga,
+        Biomes::Scorched,
+        Biomes::Bare,
+        Biomes::Tundra,
+        Biomes::Snow,
+    ];
+
+    /// A stable numeric id for the biome, new biomes are only ever added to the end. Custom biomes come after all
+    /// of the built-in ones.
+    pub fn get_id(&self) -> u32 {
+        match self {
+            Biomes::Custom(id) => Biomes::ALL.len() as u32 + *id as u32,
+            biome => Biomes::ALL.iter().position(|built_in| built_in == biome).unwrap_or_default() as u32,
+        }
+    }
+
+    /// Whether the biome is one of the final whittaker biomes, rather than water or an intermediate land type.
+    pub fn is_whittaker(&self) -> bool {
+        !matches!(
+            self,
+            Biomes::Void | Biomes::Placeholder | Biomes::FreshWater | Biomes::SaltWater | Biomes::Land | Biomes::Beach
+        )
+    }
+
+    pub fn get_symbol(&self) -> &'static str {
         match self {
             Biomes::Placeholder => " ",
             Biomes::Void => "",
@@ -81,10 +126,11 @@ impl Biomes {
             Biomes::Bare => ".",
             Biomes::Tundra => "t",
             Biomes::Snow => "s",
+            Biomes::Custom(_) => "?",
         }
     }
 
-    pub fn get_name(&self) -> &str {
+    pub fn get_name(&self) -> &'static str {
         match self {
             Biomes::Void => "Void",
             Biomes::FreshWater => "Fresh Water",
@@ -105,6 +151,7 @@ impl Biomes {
             Biomes::Bare => "Bare",
             Biomes::Tundra => "Tundra",
             Biomes::Snow => "Snow",
+            Biomes::Custom(_) => "Custom",
         }
     }
 
@@ -129,6 +176,7 @@ impl Biomes {
             Biomes::Bare => image::Rgb([187, 187, 187]),
             Biomes::Tundra => image::Rgb([221, 221, 186]),
             Biomes::Snow => image::Rgb([255, 255, 255]),
+            Biomes::Custom(_) => image::Rgb([255, 0, 255]),
         }
     }
 }
\ No newline at end of file
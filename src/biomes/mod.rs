@@ -1,16 +1,24 @@
-use crate::biomes::biomes::WHITTAKER;
+use crate::biomes::table::BiomeTable;
 use image::Rgb;
+use serde::{Deserialize, Serialize};
 
 use self::biomes::Biomes;
 
+#[allow(clippy::module_inception)]
 pub mod biomes;
+pub mod table;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Biome {
     pub tile_type: Biomes,
     pub distance_from_sea: u32,
     pub distance_from_fresh_water: u32,
+    /// the continuous height of the tile, 0.0 at sea level up to 1.0 at the highest peak
+    pub height: f32,
+    /// the whittaker elevation band (1-4) the height falls into
     pub elevation: u32,
+    /// how warm the tile is, 0.0 at the coldest up to 1.0 at the hottest
+    pub temperature: f32,
     pub moisture: u32,
 }
 
@@ -20,7 +28,9 @@ impl Biome {
             tile_type: biome,
             distance_from_sea: 0,
             distance_from_fresh_water: 0,
+            height: 0.0,
             elevation: 1,
+            temperature: 0.0,
             moisture: 0,
         }
     }
@@ -30,11 +40,28 @@ impl Biome {
             tile_type: Biomes::Void,
             distance_from_sea: 0,
             distance_from_fresh_water: 0,
+            height: 0.0,
             elevation: 1,
+            temperature: 0.0,
             moisture: 0,
         }
     }
 
+    /// Converts a continuous height into one of the 4 whittaker elevation bands.
+    pub fn elevation_band(height: f32) -> u32 {
+        ((height * 4.0).ceil() as u32).clamp(1, 4)
+    }
+
+    /// Converts a temperature into one of the 4 whittaker temperature bands, 1 being the hottest.
+    pub fn temperature_band(temperature: f32) -> u32 {
+        (((1.0 - temperature) * 4.0).ceil() as u32).clamp(1, 4)
+    }
+
+    /// Whether the tile is land rather than water, or not generated yet.
+    pub fn is_land(&self) -> bool {
+        !matches!(self.tile_type, Biomes::SaltWater | Biomes::FreshWater | Biomes::Void)
+    }
+
     pub fn get_tile_symbol(&self) -> &str {
         self.tile_type.get_symbol()
     }
@@ -47,12 +74,17 @@ impl Biome {
         self.tile_type.get_colour()
     }
 
-    pub fn get_elevation_colour(&self) -> Rgb<u8> {
-        if self.get_tile_name() == Biomes::SaltWater.get_name() {
-            return self.get_tile_colour();
+    /// The colour of the elevation band, the sea keeps its colour from the table.
+    pub fn get_elevation_colour(&self, table: &BiomeTable) -> Rgb<u8> {
+        if self.tile_type == Biomes::SaltWater {
+            return table.get_colour(self.tile_type);
         }
 
-        match self.elevation {
+        Biome::get_elevation_band_colour(self.elevation)
+    }
+
+    pub fn get_elevation_band_colour(elevation: u32) -> Rgb<u8> {
+        match elevation {
             1 => image::Rgb([0, 0, 0]),
             2 => image::Rgb([89, 89, 89]),
             3 => image::Rgb([184, 184, 184]),
@@ -61,12 +93,30 @@ impl Biome {
         }
     }
 
+    /// The colour of the temperature band, tiles which are not climate biomes keep their colour from the table.
+    pub fn get_temperature_colour(&self, table: &BiomeTable) -> Rgb<u8> {
+        if self.tile_type != Biomes::Placeholder && !self.tile_type.is_whittaker() {
+            return table.get_colour(self.tile_type);
+        }
+
+        match Biome::temperature_band(self.temperature) {
+            1 => image::Rgb([213, 0, 0]),
+            2 => image::Rgb([255, 145, 0]),
+            3 => image::Rgb([130, 200, 255]),
+            _ => image::Rgb([235, 245, 255]),
+        }
+    }
+
     pub fn get_moisture_colour(&self) -> Rgb<u8> {
-        if self.get_tile_name() == Biomes::SaltWater.get_name() {
+        if self.tile_type == Biomes::SaltWater {
             return image::Rgb([199, 0, 57]);
         }
 
-        match self.moisture {
+        Biome::get_moisture_band_colour(self.moisture)
+    }
+
+    pub fn get_moisture_band_colour(moisture: u32) -> Rgb<u8> {
+        match moisture {
             1 => image::Rgb([224, 224, 224]),
             2 => image::Rgb([112, 112, 112]),
             3 => image::Rgb([234, 242, 255]),
@@ -77,26 +127,9 @@ impl Biome {
         }
     }
 
-    pub fn calculate_biome(&mut self) {
-        let mut moisture = self.moisture;
-        let mut elevation = self.elevation;
-
-        if elevation < 1 {
-            elevation = 1;
-        }
-
-        if elevation > 4 {
-            elevation = 4;
-        }
-
-        if moisture < 1 {
-            moisture = 1;
-        }
-
-        if moisture > 6 {
-            moisture = 6;
-        }
-
-        self.tile_type = WHITTAKER[(elevation - 1) as usize][(moisture - 1) as usize];
+    /// Picks the biome which grows in the tile's climate, see [`BiomeTable::place`].
+    pub fn calculate_biome(&mut self, table: &BiomeTable) {
+        self.elevation = Biome::elevation_band(self.height);
+        self.tile_type = table.place(self);
     }
 }
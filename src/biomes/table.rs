@@ -0,0 +1,346 @@
+use crate::biomes::biomes::{Biomes, WHITTAKER};
+use crate::biomes::Biome;
+use crate::error::GeneratorError;
+use image::Rgb;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::Path;
+
+// the generator grades the moisture of the land in this many stages, from 1 (driest) up
+const MOISTURE_STAGES: u32 = 6;
+
+/// How a biome looks in the outputs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BiomeStyle {
+    pub biome: Biomes,
+    pub name: String,
+    /// the character(s) the biome is written as in the text output
+    pub symbol: String,
+    pub colour: [u8; 3],
+}
+
+impl BiomeStyle {
+    /// The built-in style of a biome.
+    pub fn new(biome: Biomes) -> Self {
+        Self {
+            biome,
+            name: biome.get_name().to_string(),
+            symbol: biome.get_symbol().to_string(),
+            colour: biome.get_colour().0,
+        }
+    }
+}
+
+/// Where a custom biome grows, every range is inclusive and a range which is left out always matches.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlacementRule {
+    /// from 0.0 at the coldest up to 1.0 at the hottest
+    #[serde(default)]
+    pub temperature: Option<[f32; 2]>,
+    /// in moisture stages, from 1 at the driest up to 6 at the wettest
+    #[serde(default)]
+    pub moisture: Option<[u32; 2]>,
+    /// from 0.0 at sea level up to 1.0 at the highest peak
+    #[serde(default)]
+    pub height: Option<[f32; 2]>,
+    /// in tiles from the nearest sea
+    #[serde(default)]
+    pub distance_from_sea: Option<[u32; 2]>,
+    /// in tiles from the nearest fresh water
+    #[serde(default)]
+    pub distance_from_fresh_water: Option<[u32; 2]>,
+}
+
+impl PlacementRule {
+    /// Whether the climate of the tile falls inside every range of the rule.
+    pub fn matches(&self, tile: &Biome) -> bool {
+        fn within<T: PartialOrd>(range: &Option<[T; 2]>, value: T) -> bool {
+            range.as_ref().is_none_or(|[from, to]| *from <= value && value <= *to)
+        }
+
+        within(&self.temperature, tile.temperature)
+            && within(&self.moisture, tile.moisture)
+            && within(&self.height, tile.height)
+            && within(&self.distance_from_sea, tile.distance_from_sea)
+            && within(&self.distance_from_fresh_water, tile.distance_from_fresh_water)
+    }
+}
+
+/// A biome which is not built in, registered with [`BiomeTable::register`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BiomeDefinition {
+    pub name: String,
+    /// the character(s) the biome is written as in the text output
+    pub symbol: String,
+    pub colour: [u8; 3],
+    /// where the biome grows, ahead of the table. Without a rule the biome only grows where the table places it
+    #[serde(default)]
+    pub rule: Option<PlacementRule>,
+}
+
+/// Which biome grows in which climate, and how every biome looks, see [`BiomeTable::from_reader`] for the file
+/// format. It is also the registry of custom biomes, see [`BiomeTable::register`].
+///
+/// The table is a grid of bands, rows go from the hottest to the coldest temperature band and columns from the
+/// driest to the wettest moisture band. The default is the whittaker diagram with 4 by 6 bands.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawBiomeTable", into = "RawBiomeTable")]
+pub struct BiomeTable {
+    temperature_bands: u32,
+    moisture_bands: u32,
+    table: Vec<Vec<Biomes>>,
+    // one style for every built-in biome, in the order of their ids
+    biomes: Vec<BiomeStyle>,
+    // the custom biomes, in the order of their ids
+    custom: Vec<BiomeDefinition>,
+}
+
+// the unchecked table as it comes out of a file, biomes without a style keep their built-in one
+#[derive(Serialize, Deserialize)]
+struct RawBiomeTable {
+    temperature_bands: u32,
+    moisture_bands: u32,
+    table: Vec<Vec<Biomes>>,
+    #[serde(default)]
+    biomes: Vec<BiomeStyle>,
+    #[serde(default)]
+    custom: Vec<BiomeDefinition>,
+}
+
+impl From<BiomeTable> for RawBiomeTable {
+    fn from(table: BiomeTable) -> Self {
+        Self {
+            temperature_bands: table.temperature_bands,
+            moisture_bands: table.moisture_bands,
+            table: table.table,
+            biomes: table.biomes,
+            custom: table.custom,
+        }
+    }
+}
+
+impl TryFrom<RawBiomeTable> for BiomeTable {
+    type Error = String;
+
+    fn try_from(raw: RawBiomeTable) -> Result<Self, Self::Error> {
+        if raw.temperature_bands == 0 || raw.moisture_bands == 0 {
+            return Err(format!(
+                "the table needs at least 1 band of each, got {}x{}",
+                raw.temperature_bands,
+                raw.moisture_bands
+            ));
+        }
+
+        if raw.table.len() != raw.temperature_bands as usize {
+            return Err(format!(
+                "the table has {} rows, expected one for each of the {} temperature bands",
+                raw.table.len(),
+                raw.temperature_bands
+            ));
+        }
+
+        if let Some((row, cells)) = raw.table.iter().enumerate().find(|(_, cells)| cells.len() != raw.moisture_bands as usize) {
+            return Err(format!(
+                "row {} of the table has {} biomes, expected one for each of the {} moisture bands",
+                row + 1,
+                cells.len(),
+                raw.moisture_bands
+            ));
+        }
+
+        if raw.custom.len() > u16::MAX as usize + 1 {
+            return Err(format!("at most {} custom biomes are supported", u16::MAX as usize + 1));
+        }
+
+        let is_known = |biome: &Biomes| match biome {
+            Biomes::Custom(id) => (*id as usize) < raw.custom.len(),
+            _ => true,
+        };
+
+        if let Some(biome) = raw.table.iter().flatten().find(|biome| !is_known(biome)) {
+            return Err(format!("the table places {:?}, which is not defined", biome));
+        }
+
+        let mut biomes: Vec<BiomeStyle> = Biomes::ALL.iter().map(|biome| BiomeStyle::new(*biome)).collect();
+        let mut styled: Vec<Biomes> = Vec::new();
+
+        for style in raw.biomes {
+            if let Biomes::Custom(_) = style.biome {
+                return Err(format!("{:?} is styled in its definition, not in the biomes", style.biome));
+            }
+
+            if styled.contains(&style.biome) {
+                return Err(format!("{} is styled more than once", style.biome.get_name()));
+            }
+
+            let id = style.biome.get_id() as usize;
+
+            styled.push(style.biome);
+            biomes[id] = style;
+        }
+
+        Ok(Self {
+            temperature_bands: raw.temperature_bands,
+            moisture_bands: raw.moisture_bands,
+            table: raw.table,
+            biomes,
+            custom: raw.custom,
+        })
+    }
+}
+
+impl Default for BiomeTable {
+    fn default() -> Self {
+        Self {
+            temperature_bands: WHITTAKER.len() as u32,
+            moisture_bands: WHITTAKER[0].len() as u32,
+            table: WHITTAKER.iter().map(|row| row.to_vec()).collect(),
+            biomes: Biomes::ALL.iter().map(|biome| BiomeStyle::new(*biome)).collect(),
+            custom: Vec::new(),
+        }
+    }
+}
+
+impl BiomeTable {
+    /// Reads a table from json, eg.
+    ///
+    /// ```json
+    /// {
+    ///   "temperature_bands": 2,
+    ///   "moisture_bands": 3,
+    ///   "table": [
+    ///     ["SubtropicalDesert", "Grassland", "TropicalRainForest"],
+    ///     ["Tundra", "Taiga", "Snow"]
+    ///   ],
+    ///   "biomes": [
+    ///     { "biome": "Snow", "name": "Glacier", "symbol": "*", "colour": [230, 240, 255] }
+    ///   ],
+    ///   "custom": [
+    ///     { "name": "Swamp", "symbol": "%", "colour": [90, 110, 70], "rule": { "moisture": [5, 6], "height": [0.0, 0.2] } }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// The `biomes` only need to list the built-in biomes which look different from their built-in style. The
+    /// `custom` biomes get the ids `{"Custom": 0}`, `{"Custom": 1}` and so on, which the table can place as well.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, GeneratorError> {
+        serde_json::from_reader(reader).map_err(|err| GeneratorError::InvalidConfig(format!("biome table: {}", err)))
+    }
+
+    /// Reads a table from a json file, see [`BiomeTable::from_reader`].
+    pub fn load(file_name: &Path) -> Result<Self, GeneratorError> {
+        Self::from_reader(BufReader::new(File::open(file_name)?))
+    }
+
+    /// Writes the table as json, including the style of every biome, so it can be used as a starting point.
+    pub fn to_writer<W: Write>(&self, writer: W) -> Result<(), GeneratorError> {
+        serde_json::to_writer_pretty(writer, self)?;
+
+        Ok(())
+    }
+
+    pub fn temperature_bands(&self) -> u32 {
+        self.temperature_bands
+    }
+
+    pub fn moisture_bands(&self) -> u32 {
+        self.moisture_bands
+    }
+
+    /// Registers a custom biome and returns it, the biome can then be placed in the table with
+    /// [`BiomeTable::set_biome`] or by its own placement rule. At most 65536 custom biomes can be registered.
+    pub fn register(&mut self, definition: BiomeDefinition) -> Result<Biomes, GeneratorError> {
+        let id = u16::try_from(self.custom.len()).map_err(|_| {
+            GeneratorError::InvalidConfig(format!("at most {} custom biomes are supported", u16::MAX as usize + 1))
+        })?;
+
+        self.custom.push(definition);
+
+        Ok(Biomes::Custom(id))
+    }
+
+    /// The custom biomes, in the order they were registered.
+    pub fn custom_biomes(&self) -> impl Iterator<Item = (Biomes, &BiomeDefinition)> {
+        self.custom.iter().enumerate().map(|(id, definition)| (Biomes::Custom(id as u16), definition))
+    }
+
+    /// The definition of a custom biome, `None` for built-in biomes and custom biomes which are not registered.
+    pub fn get_definition(&self, biome: Biomes) -> Option<&BiomeDefinition> {
+        match biome {
+            Biomes::Custom(id) => self.custom.get(id as usize),
+            _ => None,
+        }
+    }
+
+    /// Places a biome in one cell of the table, counting the bands from 1 like [`Biome::temperature_band`].
+    pub fn set_biome(&mut self, temperature_band: u32, moisture_band: u32, biome: Biomes) -> Result<(), GeneratorError> {
+        if !(1..=self.temperature_bands).contains(&temperature_band) || !(1..=self.moisture_bands).contains(&moisture_band) {
+            return Err(GeneratorError::InvalidConfig(format!(
+                "band {}x{} is outside of the {}x{} table",
+                temperature_band,
+                moisture_band,
+                self.temperature_bands,
+                self.moisture_bands
+            )));
+        }
+
+        if let Biomes::Custom(_) = biome {
+            if self.get_definition(biome).is_none() {
+                return Err(GeneratorError::InvalidConfig(format!("{:?} is not registered", biome)));
+            }
+        }
+
+        self.table[temperature_band as usize - 1][moisture_band as usize - 1] = biome;
+
+        Ok(())
+    }
+
+    /// The biome which grows on a land tile, the first custom biome whose rule matches the tile or otherwise the
+    /// biome in the table.
+    pub fn place(&self, tile: &Biome) -> Biomes {
+        self.custom_biomes()
+            .find(|(_, definition)| definition.rule.as_ref().is_some_and(|rule| rule.matches(tile)))
+            .map(|(biome, _)| biome)
+            .unwrap_or_else(|| self.get_biome(tile.temperature, tile.moisture))
+    }
+
+    /// The biome of land with the given temperature (0.0 to 1.0) and moisture stage (1 to 6) in the table. The moisture stages
+    /// are spread evenly over the moisture bands, a table with more than 6 moisture bands skips some of them.
+    pub fn get_biome(&self, temperature: f32, moisture: u32) -> Biomes {
+        let row = ((1.0 - temperature) * self.temperature_bands as f32).ceil() as u32;
+        let row = row.clamp(1, self.temperature_bands) - 1;
+        let column = (moisture.clamp(1, MOISTURE_STAGES) * self.moisture_bands).div_ceil(MOISTURE_STAGES) - 1;
+
+        self.table[row as usize][column as usize]
+    }
+
+    /// The style of a built-in biome, custom biomes are styled by their [`BiomeDefinition`].
+    pub fn get_style(&self, biome: Biomes) -> Option<&BiomeStyle> {
+        self.biomes.get(biome.get_id() as usize).filter(|style| style.biome == biome)
+    }
+
+    pub fn get_name(&self, biome: Biomes) -> &str {
+        match (self.get_style(biome), self.get_definition(biome)) {
+            (Some(style), _) => &style.name,
+            (_, Some(definition)) => &definition.name,
+            _ => biome.get_name(),
+        }
+    }
+
+    pub fn get_symbol(&self, biome: Biomes) -> &str {
+        match (self.get_style(biome), self.get_definition(biome)) {
+            (Some(style), _) => &style.symbol,
+            (_, Some(definition)) => &definition.symbol,
+            _ => biome.get_symbol(),
+        }
+    }
+
+    pub fn get_colour(&self, biome: Biomes) -> Rgb<u8> {
+        match (self.get_style(biome), self.get_definition(biome)) {
+            (Some(style), _) => Rgb(style.colour),
+            (_, Some(definition)) => Rgb(definition.colour),
+            _ => biome.get_colour(),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use crate::error::GeneratorError;
+use crate::generator::Config;
+use crate::generator::Generator;
+use crate::generator::validate_layout;
+use crate::steppers::StepperBehaviour;
+use crate::biomes::table::BiomeTable;
+use crate::land_mask::LandMask;
+use crate::preset::Preset;
+use crate::shape::Shape;
+use crate::topology::Topology;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+
+// how many letters and digits a random seed has
+const RANDOM_SEED_LENGTH: usize = 32;
+
+/// Collects and validates the generator settings, and applies them all at once when the generator is built.
+pub struct GeneratorBuilder {
+    seed: String,
+    width: u32,
+    height: u32,
+    steppers: u32,
+    steps: u32,
+    rivers: u32,
+    equator: f32,
+    debug: bool,
+    parallel: bool,
+    topology: Topology,
+    shape: Shape,
+    biome_table: BiomeTable,
+    land_mask: Option<LandMask>,
+    coast_steppers: u32,
+    coast_steps: u32,
+    preset: Option<Preset>,
+    features: Vec<(Box<dyn StepperBehaviour>, u32, u32)>,
+}
+
+impl Default for GeneratorBuilder {
+    fn default() -> Self {
+        Self {
+            seed: String::new(),
+            width: 300,
+            height: 300,
+            steppers: 350,
+            steps: 300,
+            rivers: 2,
+            equator: 0.5,
+            debug: false,
+            parallel: true,
+            topology: Topology::Bounded,
+            shape: Shape::Square,
+            biome_table: BiomeTable::default(),
+            land_mask: None,
+            coast_steppers: 0,
+            coast_steps: 30,
+            preset: None,
+            features: Vec::new(),
+        }
+    }
+}
+
+impl GeneratorBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The seed the map grows from, a random one is picked when it is left out or blank.
+    pub fn seed(mut self, seed: impl Into<String>) -> Self {
+        self.seed = seed.into();
+        self
+    }
+
+    /// Sets both the width and the height, for a square map.
+    pub fn map_size(mut self, map_size: u32) -> Self {
+        self.width = map_size;
+        self.height = map_size;
+        self
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn steppers(mut self, steppers: u32) -> Self {
+        self.steppers = steppers;
+        self
+    }
+
+    pub fn steps(mut self, steps: u32) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    pub fn rivers(mut self, rivers: u32) -> Self {
+        self.rivers = rivers;
+        self
+    }
+
+    /// The row of the equator, as a fraction of the map height from the top (0.0) to the bottom (1.0).
+    pub fn equator(mut self, equator: f32) -> Self {
+        self.equator = equator;
+        self
+    }
+
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Spreads the work across threads, the generated map is the same either way.
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Which edges of the map wrap around, see [`Topology`].
+    pub fn topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Whether the tiles are squares or hexes, see [`Shape`].
+    pub fn shape(mut self, shape: Shape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Which biome grows in which climate and how the biomes look, see [`BiomeTable`].
+    pub fn biome_table(mut self, biome_table: BiomeTable) -> Self {
+        self.biome_table = biome_table;
+        self
+    }
+
+    /// Shapes the land after the mask instead of with the landmass steppers, see [`LandMask`].
+    pub fn land_mask(mut self, land_mask: LandMask) -> Self {
+        self.land_mask = Some(land_mask);
+        self
+    }
+
+    /// How many steppers roughen up the coast of the land mask, half of them add land and half wash it away.
+    pub fn coast_steppers(mut self, coast_steppers: u32) -> Self {
+        self.coast_steppers = coast_steppers;
+        self
+    }
+
+    /// How many steps each coast stepper takes.
+    pub fn coast_steps(mut self, coast_steps: u32) -> Self {
+        self.coast_steps = coast_steps;
+        self
+    }
+
+    /// Shapes the world after a preset, see [`Preset`]. This also sets the number of steppers and steps, call
+    /// [`GeneratorBuilder::steppers`] or [`GeneratorBuilder::steps`] afterwards to change them.
+    pub fn preset(mut self, preset: Preset) -> Self {
+        self.steppers = preset.steppers();
+        self.steps = preset.steps();
+        self.preset = Some(preset);
+        self
+    }
+
+    /// See [`Generator::add_stepper`].
+    pub fn add_stepper(mut self, behaviour: Box<dyn StepperBehaviour>, steppers: u32, steps: u32) -> Self {
+        self.features.push((behaviour, steppers, steps));
+        self
+    }
+
+    pub fn build(mut self) -> Result<Generator, GeneratorError> {
+        if self.seed.trim().is_empty() {
+            self.seed = rand::thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(RANDOM_SEED_LENGTH)
+                .map(char::from)
+                .collect();
+        }
+
+        validate_layout(self.width, self.height, self.shape, self.topology, self.equator)?;
+
+        if self.steppers == 0 {
+            return Err(GeneratorError::InvalidConfig("at least 1 stepper is required".to_string()));
+        }
+
+        if self.steps == 0 {
+            return Err(GeneratorError::InvalidConfig("steppers must take at least 1 step".to_string()));
+        }
+
+        if self.coast_steppers > 0 && self.coast_steps == 0 {
+            return Err(GeneratorError::InvalidConfig("coast steppers must take at least 1 step".to_string()));
+        }
+
+        let mut generator = Generator::new(self.seed, self.width, self.height);
+
+        // the preset comes first, so the steppers and steps set on the builder win
+        if let Some(preset) = self.preset {
+            generator.set(Config::Preset(preset));
+        }
+
+        generator
+            .set(Config::Steppers(self.steppers))
+            .set(Config::Steps(self.steps))
+            .set(Config::Rivers(self.rivers))
+            .set(Config::Equator(self.equator))
+            .set(Config::Debugging(self.debug))
+            .set(Config::Parallel(self.parallel))
+            .set(Config::Topology(self.topology))
+            .set(Config::Shape(self.shape))
+            .set(Config::BiomeTable(self.biome_table))
+            .set(Config::CoastSteppers(self.coast_steppers))
+            .set(Config::CoastSteps(self.coast_steps));
+
+        if let Some(land_mask) = self.land_mask {
+            generator.set(Config::LandMask(land_mask));
+        }
+
+        for (behaviour, steppers, steps) in self.features {
+            generator.add_stepper(behaviour, steppers, steps);
+        }
+
+        Ok(generator)
+    }
+}
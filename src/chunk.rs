@@ -0,0 +1,170 @@
+use crate::biomes::biomes::Biomes;
+use crate::biomes::table::BiomeTable;
+use crate::biomes::Biome;
+use crate::error::GeneratorError;
+use crate::generator::{COAST_RAMP, COAST_WEIGHT, LAPSE_RATE, NOISE_CONTRAST, NOISE_OCTAVES};
+use crate::grid::Grid;
+use crate::noise::Noise;
+use crate::steppers::direction::Direction;
+use crate::steppers::map_position::MapPosition;
+use crate::MapData;
+use rand_pcg::Pcg64;
+use rand_seeder::Seeder;
+
+// the width in tiles of a continent and its oceans, and of the hills and valleys on the land
+const CONTINENT_SCALE: f64 = 256.0;
+const TERRAIN_SCALE: f64 = 64.0;
+// the width in tiles of the warm and cold belts, and of the wet and dry ones, across the world
+const TEMPERATURE_SCALE: f64 = 512.0;
+const MOISTURE_SCALE: f64 = 128.0;
+// continent noise above this value is land, the higher it is the more of the world is sea
+const SEA_LEVEL: f64 = 0.05;
+
+/// A square piece of an endless world, see [`generate_chunk`].
+#[derive(Clone, Debug)]
+pub struct Chunk {
+    pub chunk_x: i32,
+    pub chunk_y: i32,
+    pub map_data: MapData,
+}
+
+impl Chunk {
+    /// The number of tiles along each side of the chunk.
+    pub fn size(&self) -> u32 {
+        self.map_data.width()
+    }
+
+    /// The world position of the top left tile of the chunk.
+    pub fn origin(&self) -> (i64, i64) {
+        let size = self.size() as i64;
+
+        (self.chunk_x as i64 * size, self.chunk_y as i64 * size)
+    }
+
+    /// The world position of a tile in the chunk.
+    pub fn world_position(&self, position: MapPosition) -> (i64, i64) {
+        let (x, y) = self.origin();
+
+        (x + position.x as i64, y + position.y as i64)
+    }
+}
+
+/// The noise fields an endless world is sampled from, every tile only depends on its own world position so any
+/// part of the world can be generated on its own.
+struct World<'a> {
+    continent: Noise,
+    terrain: Noise,
+    temperature: Noise,
+    moisture: Noise,
+    table: &'a BiomeTable,
+}
+
+impl<'a> World<'a> {
+    fn new(seed: &str, table: &'a BiomeTable) -> Self {
+        let mut rng: Pcg64 = Seeder::from(seed).make_rng();
+
+        Self {
+            continent: Noise::new(&mut rng),
+            terrain: Noise::new(&mut rng),
+            temperature: Noise::new(&mut rng),
+            moisture: Noise::new(&mut rng),
+            table,
+        }
+    }
+
+    fn continent(&self, x: i64, y: i64) -> f64 {
+        self.continent.fractal(x as f64 / CONTINENT_SCALE, y as f64 / CONTINENT_SCALE, NOISE_OCTAVES)
+    }
+
+    fn is_land(&self, x: i64, y: i64) -> bool {
+        self.continent(x, y) > SEA_LEVEL
+    }
+
+    fn tile(&self, x: i64, y: i64) -> Biome {
+        let continent = self.continent(x, y);
+
+        if continent <= SEA_LEVEL {
+            return Biome::new(Biomes::SaltWater);
+        }
+
+        let mut tile = Biome::new(Biomes::Placeholder);
+
+        // the continent keeps the shores low and pulls the land up towards the interior, the same way the distance to
+        // the coast does on a finite map
+        let inland = ((continent - SEA_LEVEL) / (1.0 - SEA_LEVEL)) as f32;
+        let sample = self.terrain.fractal(x as f64 / TERRAIN_SCALE, y as f64 / TERRAIN_SCALE, NOISE_OCTAVES) as f32;
+        let terrain = ((sample * NOISE_CONTRAST + 1.0) / 2.0).clamp(0.0, 1.0);
+        let height = (inland / COAST_RAMP).min(1.0) * (inland * COAST_WEIGHT + terrain * (1.0 - COAST_WEIGHT));
+
+        tile.height = height.clamp(0.0, 1.0);
+
+        let climate = self.temperature.fractal(x as f64 / TEMPERATURE_SCALE, y as f64 / TEMPERATURE_SCALE, NOISE_OCTAVES);
+        let temperature = (climate as f32 * NOISE_CONTRAST + 1.0) / 2.0 - tile.height * LAPSE_RATE;
+
+        tile.temperature = temperature.clamp(0.0, 1.0);
+
+        let wetness = self.moisture.fractal(x as f64 / MOISTURE_SCALE, y as f64 / MOISTURE_SCALE, NOISE_OCTAVES) as f32;
+        let wetness = ((wetness * NOISE_CONTRAST + 1.0) / 2.0).clamp(0.0, 1.0);
+
+        tile.moisture = ((wetness * 6.0).ceil() as u32).clamp(1, 6);
+        tile.calculate_biome(self.table);
+
+        // beaches only form on low, dry shores, like on a finite map
+        if tile.elevation == 1 && tile.moisture <= 2 && self.touches_sea(x, y) {
+            tile.tile_type = Biomes::Beach;
+        }
+
+        tile
+    }
+
+    fn touches_sea(&self, x: i64, y: i64) -> bool {
+        Direction::get_extended_directions()
+            .iter()
+            .any(|direction| !self.is_land(x + direction.x as i64, y + direction.y as i64))
+    }
+}
+
+/// Generates one `chunk_size` by `chunk_size` chunk of an endless world, the chunk at `(chunk_x, chunk_y)` starts
+/// at world position `(chunk_x * chunk_size, chunk_y * chunk_size)`.
+///
+/// Every tile is worked out from its world position and the seed alone, so a chunk is the same whenever and in
+/// whatever order it is generated, and its edges line up exactly with the chunks next to it. An endless world has
+/// no landmass steppers, rivers or distances, the continents and climates come from noise instead. The world
+/// repeats itself every 131072 tiles.
+pub fn generate_chunk(seed: &str, chunk_x: i32, chunk_y: i32, chunk_size: u32) -> Result<Chunk, GeneratorError> {
+    generate_chunk_with_table(seed, chunk_x, chunk_y, chunk_size, &BiomeTable::default())
+}
+
+/// Generates a chunk like [`generate_chunk`], with the biomes placed by the given table instead of the whittaker
+/// diagram. Every chunk of a world has to be generated with the same table for their edges to line up.
+pub fn generate_chunk_with_table(
+    seed: &str,
+    chunk_x: i32,
+    chunk_y: i32,
+    chunk_size: u32,
+    table: &BiomeTable,
+) -> Result<Chunk, GeneratorError> {
+    if seed.is_empty() {
+        return Err(GeneratorError::InvalidConfig("the seed must not be empty".to_string()));
+    }
+
+    if chunk_size == 0 {
+        return Err(GeneratorError::InvalidConfig("a chunk must be at least 1 tile across".to_string()));
+    }
+
+    let world = World::new(seed, table);
+    let mut chunk = Chunk {
+        chunk_x,
+        chunk_y,
+        map_data: Grid::new(chunk_size, chunk_size, Biome::new_empty()),
+    };
+    let (origin_x, origin_y) = chunk.origin();
+
+    chunk.map_data.map_rows_mut(true, |y, row| {
+        for (x, tile) in row.iter_mut().enumerate() {
+            *tile = world.tile(origin_x + x as i64, origin_y + y as i64);
+        }
+    });
+
+    Ok(chunk)
+}
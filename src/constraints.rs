@@ -0,0 +1,201 @@
+use crate::biomes::biomes::Biomes;
+use crate::error::GeneratorError;
+use crate::regions::{RegionKind, Regions};
+use crate::stats;
+use crate::MapData;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+// how much the number of steppers may grow or shrink between two attempts to hit the land ratio
+const MAX_STEPPER_SCALE: f32 = 2.0;
+
+/// What a generated map has to look like, see
+/// [`Generator::generate_with_constraints`](crate::generator::Generator::generate_with_constraints).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Constraints {
+    land_ratio: Option<RangeInclusive<f32>>,
+    min_islands: Option<u32>,
+    min_largest_island: Option<u32>,
+    required_biomes: Vec<Biomes>,
+    min_lakes: Option<u32>,
+    max_attempts: u32,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Self {
+            land_ratio: None,
+            min_islands: None,
+            min_largest_island: None,
+            required_biomes: Vec::new(),
+            min_lakes: None,
+            max_attempts: 20,
+        }
+    }
+}
+
+impl Constraints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The share of the map which is land, from 0.0 to 1.0.
+    pub fn land_ratio(mut self, land_ratio: RangeInclusive<f32>) -> Self {
+        self.land_ratio = Some(land_ratio);
+        self
+    }
+
+    /// How many separate pieces of land the map has at least.
+    pub fn min_islands(mut self, min_islands: u32) -> Self {
+        self.min_islands = Some(min_islands);
+        self
+    }
+
+    /// How many tiles the largest piece of land has at least.
+    pub fn min_largest_island(mut self, min_largest_island: u32) -> Self {
+        self.min_largest_island = Some(min_largest_island);
+        self
+    }
+
+    /// A biome which grows somewhere on the map, can be called multiple times.
+    pub fn require_biome(mut self, biome: Biomes) -> Self {
+        if !self.required_biomes.contains(&biome) {
+            self.required_biomes.push(biome);
+        }
+        self
+    }
+
+    /// How many lakes the map has at least, rivers do not count as lakes.
+    pub fn min_lakes(mut self, min_lakes: u32) -> Self {
+        self.min_lakes = Some(min_lakes);
+        self
+    }
+
+    /// How many maps are generated before giving up.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn get_max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Whether no constraints are set, so any map will do.
+    pub fn is_empty(&self) -> bool {
+        self.land_ratio.is_none()
+            && self.min_islands.is_none()
+            && self.min_largest_island.is_none()
+            && self.required_biomes.is_empty()
+            && self.min_lakes.is_none()
+    }
+
+    pub fn validate(&self) -> Result<(), GeneratorError> {
+        if self.max_attempts == 0 {
+            return Err(GeneratorError::InvalidConfig("at least 1 attempt is required".to_string()));
+        }
+
+        if let Some(land_ratio) = &self.land_ratio {
+            if land_ratio.is_empty() || *land_ratio.start() < 0.0 || *land_ratio.end() > 1.0 {
+                return Err(GeneratorError::InvalidConfig(format!(
+                    "the land ratio must be a range within 0.0 and 1.0, got {:?}",
+                    land_ratio
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Describes every constraint the map breaks, the map meets all of them when there are none.
+    pub fn check(&self, map_data: &MapData) -> Vec<String> {
+        let mut violations = Vec::new();
+
+        if let Some(land_ratio) = &self.land_ratio {
+            let ratio = stats::land_ratio(map_data);
+
+            if !land_ratio.contains(&ratio) {
+                violations.push(format!(
+                    "{:.2} of the map is land, not between {:.2} and {:.2}",
+                    ratio,
+                    land_ratio.start(),
+                    land_ratio.end()
+                ));
+            }
+        }
+
+        let regions = Regions::landmasses(map_data);
+
+        if self.min_islands.is_some() || self.min_largest_island.is_some() {
+            let islands: Vec<usize> = regions.of_kind(RegionKind::Island).map(|island| island.area).collect();
+            let largest = islands.iter().max().copied().unwrap_or_default() as u32;
+
+            if let Some(min_islands) = self.min_islands.filter(|min_islands| (islands.len() as u32) < *min_islands) {
+                violations.push(format!("{} islands, not at least {}", islands.len(), min_islands));
+            }
+
+            if let Some(min_largest) = self.min_largest_island.filter(|min_largest| largest < *min_largest) {
+                violations.push(format!("the largest island has {} tiles, not at least {}", largest, min_largest));
+            }
+        }
+
+        let present: HashSet<Biomes> = map_data.iter().map(|tile| tile.tile_type).collect();
+
+        for biome in self.required_biomes.iter().filter(|biome| !present.contains(biome)) {
+            violations.push(format!("no {:?} on the map", biome));
+        }
+
+        if let Some(min_lakes) = self.min_lakes {
+            let lakes = regions.of_kind(RegionKind::Lake).count() as u32;
+
+            if lakes < min_lakes {
+                violations.push(format!("{} lakes, not at least {}", lakes, min_lakes));
+            }
+        }
+
+        violations
+    }
+
+    /// How much to scale the number of steppers by to bring the land ratio of the map into range, if it is out of
+    /// range.
+    pub fn stepper_scale(&self, map_data: &MapData) -> Option<f32> {
+        let land_ratio = self.land_ratio.as_ref()?;
+        let ratio = stats::land_ratio(map_data);
+
+        if land_ratio.contains(&ratio) {
+            return None;
+        }
+
+        let target = (land_ratio.start() + land_ratio.end()) / 2.0;
+
+        Some((target / ratio.max(f32::EPSILON)).clamp(1.0 / MAX_STEPPER_SCALE, MAX_STEPPER_SCALE))
+    }
+}
+
+/// Which attempt generated a map that meets the constraints, and the settings to generate it again directly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintReport {
+    /// counted from 1, the first attempt uses the seed the generator was given
+    pub attempt: u32,
+    pub seed: String,
+    pub steppers: u32,
+}
+
+impl fmt::Display for ConstraintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "constraints met on attempt {} with seed '{}' and {} steppers",
+            self.attempt, self.seed, self.steppers
+        )
+    }
+}
+
+/// The seed of an attempt, derived from the seed of the generator so the attempts are the same every time.
+pub fn attempt_seed(seed: &str, attempt: u32) -> String {
+    match attempt {
+        0 | 1 => seed.to_string(),
+        attempt => format!("{}-{}", seed, attempt),
+    }
+}
@@ -0,0 +1,138 @@
+use crate::biomes::biomes::Biomes;
+use crate::grid::Grid;
+use crate::shape::Shape;
+use crate::MapData;
+use std::collections::VecDeque;
+
+// a squared distance larger than any on a real map, but finite so that subtracting two of them in the parabola
+// intersections does not give NaN
+const UNREACHABLE: f64 = 1e20;
+
+/// Calculates the exact euclidean distance from every tile on the map to the nearest tile of the given biome.
+///
+/// Uses the separable distance transform by Felzenszwalb & Huttenlocher, which runs in linear time over the
+/// number of tiles. Distances are measured across the wrapping edges of the map's topology. Tiles are
+/// `f32::INFINITY` away when the biome does not exist anywhere on the map.
+///
+/// Hex maps count the number of hexes to walk instead, see [`hex_distance_field`].
+pub fn distance_field(map_data: &MapData, biome: Biomes) -> Grid<f32> {
+    if map_data.shape() == Shape::Hex {
+        return hex_distance_field(map_data, biome);
+    }
+
+    let width = map_data.width() as usize;
+    let height = map_data.height() as usize;
+    let topology = map_data.topology();
+
+    let mut squared: Grid<f64> = map_data
+        .map(|tile| if tile.tile_type == biome { 0.0 } else { UNREACHABLE });
+
+    // transform along x
+    for row in squared.rows_mut() {
+        let transformed = if topology.wraps_x() { transform_wrapped_line(row) } else { transform_line(row) };
+        row.copy_from_slice(&transformed);
+    }
+
+    // transform along y
+    let mut line: Vec<f64> = vec![0.0; height];
+
+    for x in 0..width {
+        for (value, cell) in line.iter_mut().zip(squared.column(x as u32)) {
+            *value = *cell;
+        }
+
+        let transformed = if topology.wraps_y() { transform_wrapped_line(&line) } else { transform_line(&line) };
+
+        for (row, value) in squared.rows_mut().zip(transformed) {
+            row[x] = value;
+        }
+    }
+
+    squared.map(|distance| if *distance >= UNREACHABLE { f32::INFINITY } else { distance.sqrt() as f32 })
+}
+
+/// The number of steps from every hex to the nearest hex of the given biome, walking from hex to hex.
+///
+/// A breadth first search from every hex of the biome at once, which runs in linear time over the number of hexes.
+pub fn hex_distance_field(map_data: &MapData, biome: Biomes) -> Grid<f32> {
+    let mut distances = map_data.map(|_| f32::INFINITY);
+    let mut queue = VecDeque::new();
+
+    for (position, tile) in map_data.enumerate() {
+        if tile.tile_type == biome {
+            distances[position] = 0.0;
+            queue.push_back(position);
+        }
+    }
+
+    while let Some(position) = queue.pop_front() {
+        let distance = distances[position] + 1.0;
+
+        for neighbour in map_data.adjacent(position) {
+            if distances[neighbour].is_infinite() {
+                distances[neighbour] = distance;
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    distances
+}
+
+/// One dimensional squared distance transform, the lower envelope of the parabolas rooted at each cell.
+fn transform_line(values: &[f64]) -> Vec<f64> {
+    let len = values.len();
+    let mut distances = vec![0.0; len];
+    let mut vertices: Vec<usize> = vec![0; len];
+    let mut boundaries: Vec<f64> = vec![0.0; len + 1];
+    let mut k = 0;
+
+    if len == 0 {
+        return distances;
+    }
+
+    boundaries[0] = f64::NEG_INFINITY;
+    boundaries[1] = f64::INFINITY;
+
+    for q in 1..len {
+        let mut intersection = intersect(values, vertices[k], q);
+
+        while intersection <= boundaries[k] {
+            k -= 1;
+            intersection = intersect(values, vertices[k], q);
+        }
+
+        k += 1;
+        vertices[k] = q;
+        boundaries[k] = intersection;
+        boundaries[k + 1] = f64::INFINITY;
+    }
+
+    k = 0;
+
+    for (q, distance) in distances.iter_mut().enumerate() {
+        while boundaries[k + 1] < q as f64 {
+            k += 1;
+        }
+
+        let offset = q as f64 - vertices[k] as f64;
+        *distance = offset * offset + values[vertices[k]];
+    }
+
+    distances
+}
+
+/// Same as [`transform_line`] for a line whose ends join up. The closest tile is never more than half the line
+/// away, so laying a copy of the line on either side is enough to see past the seam.
+fn transform_wrapped_line(values: &[f64]) -> Vec<f64> {
+    let len = values.len();
+    let tiled: Vec<f64> = values.iter().chain(values).chain(values).copied().collect();
+
+    transform_line(&tiled)[len..2 * len].to_vec()
+}
+
+fn intersect(values: &[f64], p: usize, q: usize) -> f64 {
+    let (p_f, q_f) = (p as f64, q as f64);
+
+    ((values[q] + q_f * q_f) - (values[p] + p_f * p_f)) / (2.0 * q_f - 2.0 * p_f)
+}
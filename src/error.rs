@@ -0,0 +1,71 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum GeneratorError {
+    /// reading or writing a file failed
+    Io(io::Error),
+    /// encoding or decoding an image failed
+    Image(image::ImageError),
+    /// the generator was configured with missing or out of range values
+    InvalidConfig(String),
+    /// encoding or decoding a json map failed
+    Json(serde_json::Error),
+    /// encoding or decoding a binary map failed
+    Binary(bincode::Error),
+    /// the generated map cannot be processed any further, eg. it contains no land or no sea
+    DegenerateMap(String),
+    /// none of the maps generated met the constraints, see [`Constraints`](crate::constraints::Constraints)
+    UnmetConstraints(String),
+}
+
+impl fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratorError::Io(err) => write!(f, "io error: {}", err),
+            GeneratorError::Image(err) => write!(f, "image error: {}", err),
+            GeneratorError::Json(err) => write!(f, "json error: {}", err),
+            GeneratorError::Binary(err) => write!(f, "binary error: {}", err),
+            GeneratorError::InvalidConfig(msg) => write!(f, "invalid config: {}", msg),
+            GeneratorError::DegenerateMap(msg) => write!(f, "degenerate map: {}", msg),
+            GeneratorError::UnmetConstraints(msg) => write!(f, "unmet constraints: {}", msg),
+        }
+    }
+}
+
+impl Error for GeneratorError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GeneratorError::Io(err) => Some(err),
+            GeneratorError::Image(err) => Some(err),
+            GeneratorError::Json(err) => Some(err),
+            GeneratorError::Binary(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GeneratorError {
+    fn from(err: io::Error) -> Self {
+        GeneratorError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for GeneratorError {
+    fn from(err: serde_json::Error) -> Self {
+        GeneratorError::Json(err)
+    }
+}
+
+impl From<bincode::Error> for GeneratorError {
+    fn from(err: bincode::Error) -> Self {
+        GeneratorError::Binary(err)
+    }
+}
+
+impl From<image::ImageError> for GeneratorError {
+    fn from(err: image::ImageError) -> Self {
+        GeneratorError::Image(err)
+    }
+}
@@ -0,0 +1,61 @@
+use crate::biomes::Biome;
+use crate::shape::axial_to_offset;
+use crate::MapData;
+use image::{Rgb, RgbImage};
+
+// the hexes are pointy topped, so they are sqrt(3) times wider than the length of a side
+const SQRT_3: f64 = 1.732_050_807_568_877_2;
+
+/// The size in pixels of one rendering of a hex map, with hexes `hex_width` pixels across. Odd rows stick out
+/// half a hex on the right, and the rows overlap by a quarter of a hex.
+pub fn hex_panel_size(map_data: &MapData, hex_width: u32) -> (u32, u32) {
+    let side = hex_width as f64 / SQRT_3;
+    let width = hex_width as f64 * (map_data.width() as f64 + 0.5);
+    let height = side * 1.5 * (map_data.height() as f64 - 1.0) + side * 2.0;
+
+    (width.ceil() as u32, height.ceil() as u32)
+}
+
+/// Draws every hex of the map in its colour, starting `offset` pixels down the image. Pixels between the edge of
+/// the image and the ragged edge of the map are left untouched, unless the map wraps around that edge.
+pub fn draw_hex_panel(
+    image: &mut RgbImage,
+    map_data: &MapData,
+    offset: u32,
+    hex_width: u32,
+    colour: &dyn Fn(&Biome) -> Rgb<u8>,
+) {
+    let (width, height) = hex_panel_size(map_data, hex_width);
+    let side = hex_width as f64 / SQRT_3;
+
+    for py in 0..height {
+        for px in 0..width {
+            // measured from the middle of the first hex
+            let x = px as f64 + 0.5 - hex_width as f64 / 2.0;
+            let y = py as f64 + 0.5 - side;
+            let q = (SQRT_3 / 3.0 * x - y / 3.0) / side;
+            let r = (2.0 / 3.0 * y) / side;
+            let (q, r) = round_axial(q, r);
+
+            if let Some(tile) = map_data.get(axial_to_offset(q, r)) {
+                image.put_pixel(px, py + offset, colour(tile));
+            }
+        }
+    }
+}
+
+/// Rounds fractional axial coordinates to the hex they fall in.
+fn round_axial(q: f64, r: f64) -> (i32, i32) {
+    let s = -q - r;
+    let (mut rounded_q, mut rounded_r, rounded_s) = (q.round(), r.round(), s.round());
+    let (q_diff, r_diff, s_diff) = ((rounded_q - q).abs(), (rounded_r - r).abs(), (rounded_s - s).abs());
+
+    // the three coordinates always sum to zero, so fix up the one which was rounded the furthest
+    if q_diff > r_diff && q_diff > s_diff {
+        rounded_q = -rounded_r - rounded_s;
+    } else if r_diff > s_diff {
+        rounded_r = -rounded_q - rounded_s;
+    }
+
+    (rounded_q as i32, rounded_r as i32)
+}
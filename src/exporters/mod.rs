@@ -0,0 +1,2 @@
+pub mod hex;
+pub mod tiled;
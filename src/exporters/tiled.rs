@@ -0,0 +1,231 @@
+use crate::biomes::biomes::Biomes;
+use crate::biomes::table::BiomeTable;
+use crate::biomes::Biome;
+use crate::error::GeneratorError;
+use crate::shape::Shape;
+use crate::MapData;
+use image::{ImageBuffer, Rgb, RgbImage};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const TILESET_COLUMNS: u32 = 8;
+const ELEVATION_BANDS: u32 = 4;
+const MOISTURE_BANDS: u32 = 6;
+
+// picks the tile id of a map tile for a layer, if the layer has anything to show for it
+type LayerTile = fn(&Biome) -> Option<u32>;
+
+/// The tile id of a biome in the generated tileset, custom biomes come after all of the built-in tiles.
+pub fn biome_tile_id(biome: Biomes) -> u32 {
+    match biome {
+        Biomes::Custom(id) => built_in_tile_count() + id as u32,
+        _ => biome.get_id(),
+    }
+}
+
+/// The tile id of an elevation band (1-4) in the generated tileset.
+pub fn elevation_tile_id(elevation: u32) -> u32 {
+    Biomes::ALL.len() as u32 + elevation.clamp(1, ELEVATION_BANDS) - 1
+}
+
+/// The tile id of a moisture band (1-6) in the generated tileset.
+pub fn moisture_tile_id(moisture: u32) -> u32 {
+    Biomes::ALL.len() as u32 + ELEVATION_BANDS + moisture.clamp(1, MOISTURE_BANDS) - 1
+}
+
+fn built_in_tile_count() -> u32 {
+    Biomes::ALL.len() as u32 + ELEVATION_BANDS + MOISTURE_BANDS
+}
+
+fn tile_count(table: &BiomeTable) -> u32 {
+    built_in_tile_count() + table.custom_biomes().count() as u32
+}
+
+// the custom biome behind a tile id, if it is one
+fn custom_biome(tile_id: u32) -> Option<Biomes> {
+    tile_id.checked_sub(built_in_tile_count()).map(|id| Biomes::Custom(id as u16))
+}
+
+/// Writes the map as a Tiled map (`.tmx`), with a biome, elevation and moisture tile layer. The tileset is written
+/// next to it, as a `.tsx` with the same name and the tileset image as a `_tileset.png`. The biome tiles are
+/// named and coloured after the table. A hex map is written as a staggered hexagonal map.
+pub fn export_tiled(map_data: &MapData, table: &BiomeTable, file_name: &Path, tile_size: u32) -> Result<(), GeneratorError> {
+    if tile_size == 0 {
+        return Err(GeneratorError::InvalidConfig("the tile size must be at least 1".to_string()));
+    }
+
+    let tileset_path = file_name.with_extension("tsx");
+    let stem = file_name.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let image_path = file_name.with_file_name(format!("{}_tileset.png", stem));
+    let file_stem = |path: &Path| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+
+    write_tileset_image(&image_path, table, tile_size)?;
+    write_tileset(&tileset_path, table, &file_stem(&image_path), tile_size)?;
+    write_map(map_data, file_name, &file_stem(&tileset_path), tile_size)?;
+
+    Ok(())
+}
+
+fn tile_colour(table: &BiomeTable, tile_id: u32) -> Rgb<u8> {
+    let biomes = Biomes::ALL.len() as u32;
+
+    if let Some(biome) = custom_biome(tile_id) {
+        return table.get_colour(biome);
+    }
+
+    if tile_id < biomes {
+        return table.get_colour(Biomes::ALL[tile_id as usize]);
+    }
+
+    if tile_id < biomes + ELEVATION_BANDS {
+        return Biome::get_elevation_band_colour(tile_id - biomes + 1);
+    }
+
+    Biome::get_moisture_band_colour(tile_id - biomes - ELEVATION_BANDS + 1)
+}
+
+fn tile_name(table: &BiomeTable, tile_id: u32) -> String {
+    let biomes = Biomes::ALL.len() as u32;
+
+    if let Some(biome) = custom_biome(tile_id) {
+        return table.get_name(biome).to_string();
+    }
+
+    if tile_id < biomes {
+        return table.get_name(Biomes::ALL[tile_id as usize]).to_string();
+    }
+
+    if tile_id < biomes + ELEVATION_BANDS {
+        return format!("Elevation {}", tile_id - biomes + 1);
+    }
+
+    format!("Moisture {}", tile_id - biomes - ELEVATION_BANDS + 1)
+}
+
+fn tileset_rows(table: &BiomeTable) -> u32 {
+    tile_count(table).div_ceil(TILESET_COLUMNS)
+}
+
+fn write_tileset_image(file_name: &Path, table: &BiomeTable, tile_size: u32) -> Result<(), GeneratorError> {
+    let mut image: RgbImage = ImageBuffer::new(TILESET_COLUMNS * tile_size, tileset_rows(table) * tile_size);
+
+    for tile_id in 0..tile_count(table) {
+        let colour = tile_colour(table, tile_id);
+        let x_offset = (tile_id % TILESET_COLUMNS) * tile_size;
+        let y_offset = (tile_id / TILESET_COLUMNS) * tile_size;
+
+        for x in 0..tile_size {
+            for y in 0..tile_size {
+                image.put_pixel(x_offset + x, y_offset + y, colour);
+            }
+        }
+    }
+
+    image.save(file_name)?;
+
+    Ok(())
+}
+
+fn write_tileset(file_name: &Path, table: &BiomeTable, image_source: &str, tile_size: u32) -> Result<(), GeneratorError> {
+    let mut file = BufWriter::new(File::create(file_name)?);
+
+    writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        file,
+        r#"<tileset version="1.10" name="biomes" tilewidth="{size}" tileheight="{size}" tilecount="{count}" columns="{columns}">"#,
+        size = tile_size,
+        count = tile_count(table),
+        columns = TILESET_COLUMNS,
+    )?;
+    writeln!(
+        file,
+        r#" <image source="{}" width="{}" height="{}"/>"#,
+        escape_xml(image_source),
+        TILESET_COLUMNS * tile_size,
+        tileset_rows(table) * tile_size,
+    )?;
+
+    for tile_id in 0..tile_count(table) {
+        writeln!(file, r#" <tile id="{}">"#, tile_id)?;
+        writeln!(file, "  <properties>")?;
+        writeln!(file, r#"   <property name="name" value="{}"/>"#, escape_xml(&tile_name(table, tile_id)))?;
+        writeln!(file, "  </properties>")?;
+        writeln!(file, " </tile>")?;
+    }
+
+    writeln!(file, "</tileset>")?;
+    file.flush()?;
+
+    Ok(())
+}
+
+fn write_map(map_data: &MapData, file_name: &Path, tileset_source: &str, tile_size: u32) -> Result<(), GeneratorError> {
+    let width = map_data.width();
+    let height = map_data.height();
+    let mut file = BufWriter::new(File::create(file_name)?);
+
+    // hex maps are pointy topped with the odd rows pushed half a hex to the right, like the image output, and the
+    // rows overlap by a quarter of a tile
+    let (orientation, hex_layout) = match map_data.shape() {
+        Shape::Square => ("orthogonal", String::new()),
+        Shape::Hex => (
+            "hexagonal",
+            format!(r#" hexsidelength="{}" staggeraxis="y" staggerindex="odd""#, tile_size / 2),
+        ),
+    };
+
+    writeln!(file, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        file,
+        r#"<map version="1.10" orientation="{}" renderorder="right-down" width="{}" height="{}" tilewidth="{size}" tileheight="{size}"{} infinite="0" nextlayerid="4" nextobjectid="1">"#,
+        orientation,
+        width,
+        height,
+        hex_layout,
+        size = tile_size,
+    )?;
+    writeln!(file, r#" <tileset firstgid="1" source="{}"/>"#, escape_xml(tileset_source))?;
+
+    // gid 0 is an empty cell in tiled, so every tile id is offset by the firstgid of 1
+    let layers: [(&str, LayerTile); 3] = [
+        ("biome", |tile| Some(biome_tile_id(tile.tile_type))),
+        ("elevation", |tile| tile.is_land().then(|| elevation_tile_id(tile.elevation))),
+        ("moisture", |tile| tile.is_land().then(|| moisture_tile_id(tile.moisture))),
+    ];
+
+    for (index, (name, tile_id)) in layers.iter().enumerate() {
+        writeln!(file, r#" <layer id="{}" name="{}" width="{}" height="{}">"#, index + 1, name, width, height)?;
+        writeln!(file, r#"  <data encoding="csv">"#)?;
+
+        for (y, tiles) in map_data.rows().enumerate() {
+            let row: Vec<String> = tiles
+                .iter()
+                .map(|tile| tile_id(tile).map_or(0, |id| id + 1).to_string())
+                .collect();
+            let separator = if y + 1 < height as usize { "," } else { "" };
+
+            writeln!(file, "{}{}", row.join(","), separator)?;
+        }
+
+        writeln!(file, "  </data>")?;
+        writeln!(file, " </layer>")?;
+    }
+
+    writeln!(file, "</map>")?;
+    file.flush()?;
+
+    Ok(())
+}
+
+// biome names and file names can hold anything, so they are escaped before they go into an attribute
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,368 +1,971 @@
-use crate::helper::is_valid_cell;
 use crate::biomes::biomes::Biomes;
+use crate::biomes::table::BiomeTable;
 use crate::Biome;
 use rand::Rng;
 use rand_pcg::Pcg64;
+use rayon::prelude::*;
 use rand_seeder::{Seeder};
-use image::{ImageBuffer, RgbImage};
+use image::{ImageBuffer, Rgb, RgbImage};
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufWriter, Read, Write};
 use std::path::Path;
 use crate::MapData;
-use crate::helper::get_distance;
-use crate::steppers::Generators;
+use crate::distance::distance_field;
+use crate::builder::GeneratorBuilder;
+use crate::constraints::{attempt_seed, ConstraintReport, Constraints};
+use crate::error::GeneratorError;
+use crate::exporters::hex::{draw_hex_panel, hex_panel_size};
+use crate::exporters::tiled::export_tiled;
+use crate::output::OutputFormat;
+use crate::serialization::{write_map, MapFile, MapFormat};
+use crate::regions::Regions;
+use crate::stats::MapStats;
+use crate::noise::Noise;
+use crate::land_mask::LandMask;
+use crate::preset::{Preset, StartLayout};
+use crate::steppers::erosion::Erosion;
+use crate::steppers::landmass::Landmass;
+use crate::steppers::StepperBehaviour;
 use crate::steppers::Stepper;
-use crate::steppers::direction::{Direction};
+use crate::grid::Grid;
+use crate::shape::Shape;
+use crate::topology::Topology;
 use crate::steppers::map_position::MapPosition;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
+
+// how many noise features span the width of the map
+const NOISE_FEATURES: f64 = 4.0;
+pub(crate) const NOISE_OCTAVES: u32 = 5;
+// fractal noise clusters around 0.0, stretch it so the terrain uses the full range of heights
+pub(crate) const NOISE_CONTRAST: f32 = 1.8;
+// how much the distance to the coast weighs into the height compared to the noise
+pub(crate) const COAST_WEIGHT: f32 = 0.25;
+// the share of the way to the map's furthest inland tile over which the shore rises up from sea level
+pub(crate) const COAST_RAMP: f32 = 0.2;
+// how much colder the poles are than the equator
+const LATITUDE_COOLING: f32 = 0.8;
+// how much colder the highest peak is than sea level
+pub(crate) const LAPSE_RATE: f32 = 0.45;
+// the temperature the sea pulls the coast towards, how strongly and over what share of the map size
+const OCEAN_TEMPERATURE: f32 = 0.6;
+const OCEAN_MODERATION: f32 = 0.3;
+const OCEAN_INFLUENCE: f32 = 0.05;
+// how far the coast steppers wash away the land of a land mask
+const EROSION_RADIUS: u32 = 2;
+
+// picks the colour of a map tile for one of the rendered panels
+type PanelColour<'a> = &'a dyn Fn(&Biome) -> Rgb<u8>;
 
 pub enum Config {
     Seed(String),
+    /// a square map of the given size
     MapSize(u32),
+    Width(u32),
+    Height(u32),
     Steppers(u32),
     Steps(u32),
+    Rivers(u32),
+    /// the row of the equator, as a fraction of the map height from the top (0.0) to the bottom (1.0)
+    Equator(f32),
     Debugging(bool),
+    /// spread the landmass steppers and the per tile passes across threads, the map is identical either way
+    Parallel(bool),
+    /// which edges of the map wrap around to the opposite edge
+    Topology(Topology),
+    /// whether the tiles are squares or hexes
+    Shape(Shape),
+    /// which biome grows in which climate and how the biomes look in the outputs
+    BiomeTable(BiomeTable),
+    /// the shape of the land, used instead of the landmass steppers
+    LandMask(LandMask),
+    /// how many steppers roughen up the coast of the land mask, half of them add land and half wash it away
+    CoastSteppers(u32),
+    /// how many steps each coast stepper takes
+    CoastSteps(u32),
+    /// where the landmass steppers start and how much land they paint, also sets the number of steppers and steps
+    Preset(Preset),
 }
 
 pub struct Generator {
     seed: String,
-    map_size: u32,
+    width: u32,
+    height: u32,
     debug: Option<bool>,
     steppers: Option<u32>,
     steps: Option<u32>,
+    rivers: Option<u32>,
+    equator: Option<f32>,
+    parallel: Option<bool>,
+    topology: Option<Topology>,
+    shape: Option<Shape>,
+    biome_table: BiomeTable,
+    land_mask: Option<LandMask>,
+    coast_steppers: Option<u32>,
+    coast_steps: Option<u32>,
+    preset: Option<Preset>,
+    features: Vec<(Box<dyn StepperBehaviour>, u32, u32)>,
     map_data: MapData,
+    river_paths: Vec<Vec<MapPosition>>,
     rng: Pcg64,
 }
 
+// a generator is built on one thread and handed to another, eg. a worker in a server, so it has to stay Send
+const _: () = {
+    fn assert_send<T: Send>() {}
+
+    let _ = assert_send::<Generator>;
+    let _ = assert_send::<GeneratorBuilder>;
+};
+
 impl Generator {
-    pub fn new(seed: String, map_size: u32) -> Self {
+    pub fn new(seed: String, width: u32, height: u32) -> Self {
         let rng = Seeder::from(&seed).make_rng();
 
         Self {
             seed,
-            map_data: vec![vec![Biome::new_empty(); map_size as usize]; map_size as usize],
-            rng: rng,
+            map_data: Grid::new(width, height, Biome::new_empty()),
+            river_paths: Vec::new(),
+            rng,
             debug: None,
             steppers: None,
             steps: None,
-            map_size,
+            rivers: None,
+            equator: None,
+            parallel: None,
+            topology: None,
+            shape: None,
+            biome_table: BiomeTable::default(),
+            land_mask: None,
+            coast_steppers: None,
+            coast_steps: None,
+            preset: None,
+            features: Vec::new(),
+            width,
+            height,
         }
     }
 
+    /// Restores a map previously written with [`Generator::save`] or [`Generator::output_map`].
+    pub fn load<R: Read>(reader: R, format: MapFormat) -> Result<Self, GeneratorError> {
+        let map_file = MapFile::from_reader(reader, format)?;
+        let mut generator = Generator::new(map_file.seed, map_file.map_data.width(), map_file.map_data.height());
+
+        generator.topology = Some(map_file.map_data.topology());
+        generator.shape = Some(map_file.map_data.shape());
+        generator.map_data = map_file.map_data;
+        generator.biome_table = map_file.biome_table;
+
+        Ok(generator)
+    }
+
+    /// Writes the map, including all of its distances, so it can be restored with [`Generator::load`].
+    pub fn save<W: Write>(&self, writer: W, format: MapFormat) -> Result<&Self, GeneratorError> {
+        write_map(writer, &self.seed, &self.map_data, &self.biome_table, format)?;
+
+        Ok(self)
+    }
+
+    pub fn get_seed(&self) -> &str {
+        &self.seed
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get_topology(&self) -> Topology {
+        self.topology.unwrap_or_default()
+    }
+
+    pub fn get_shape(&self) -> Shape {
+        self.shape.unwrap_or_default()
+    }
+
+    pub fn get_biome_table(&self) -> &BiomeTable {
+        &self.biome_table
+    }
+
+    pub fn get_land_mask(&self) -> Option<&LandMask> {
+        self.land_mask.as_ref()
+    }
+
+    pub fn get_preset(&self) -> Option<Preset> {
+        self.preset
+    }
+
+    /// The path of every river, from its source down to where it flows into the sea, a lake or another river, or
+    /// just ends where it cannot flow any lower. A loaded map has no rivers.
+    pub fn get_rivers(&self) -> &[Vec<MapPosition>] {
+        &self.river_paths
+    }
+
+    /// Labels the islands, lakes, rivers and oceans of the map, see [`Regions::landmasses`].
+    pub fn get_regions(&self) -> Regions {
+        Regions::landmasses(&self.map_data)
+    }
+
+    /// Labels the patches of each biome on the map, see [`Regions::biome_patches`].
+    pub fn get_biome_patches(&self) -> Regions {
+        Regions::biome_patches(&self.map_data)
+    }
+
+    /// Counts up what the map is made up of, see [`MapStats`].
+    pub fn get_stats(&self) -> MapStats {
+        MapStats::new(&self.seed, &self.map_data, &self.biome_table)
+    }
+
+    pub fn get_map_data(&self) -> &MapData {
+        &self.map_data
+    }
+
+    pub fn get_map_data_mut(&mut self) -> &mut MapData {
+        &mut self.map_data
+    }
+
+    /// Creates a builder with the same defaults as the command line.
+    pub fn builder() -> GeneratorBuilder {
+        GeneratorBuilder::default()
+    }
+
     pub fn set(&mut self, config: Config) -> &mut Self  {
         match config {
-            Config::Seed(x) => self.seed = x,
-            Config::MapSize(x) => self.map_size = x,
+            Config::Seed(x) => {
+                self.rng = Seeder::from(&x).make_rng();
+                self.seed = x;
+            }
+            Config::MapSize(x) => {
+                self.map_data = Grid::new(x, x, Biome::new_empty());
+                self.width = x;
+                self.height = x;
+            }
+            Config::Width(x) => {
+                self.map_data = Grid::new(x, self.height, Biome::new_empty());
+                self.width = x;
+            }
+            Config::Height(x) => {
+                self.map_data = Grid::new(self.width, x, Biome::new_empty());
+                self.height = x;
+            }
             Config::Steppers(x) => self.steppers = Some(x),
             Config::Steps(x) => self.steps = Some(x),
+            Config::Rivers(x) => self.rivers = Some(x),
+            Config::Equator(x) => self.equator = Some(x),
             Config::Debugging(x) => self.debug = Some(x),
+            Config::Parallel(x) => self.parallel = Some(x),
+            Config::Topology(x) => self.topology = Some(x),
+            Config::Shape(x) => self.shape = Some(x),
+            Config::BiomeTable(x) => self.biome_table = x,
+            Config::LandMask(x) => self.land_mask = Some(x),
+            Config::CoastSteppers(x) => self.coast_steppers = Some(x),
+            Config::CoastSteps(x) => self.coast_steps = Some(x),
+            Config::Preset(x) => {
+                self.steppers = Some(x.steppers());
+                self.steps = Some(x.steps());
+                self.preset = Some(x);
+            }
         }
 
         self
     }
 
-    pub fn generate(&mut self) -> &mut Self{
-        self.run();
+    /// Adds `steppers` extra steppers of `steps` steps each, which walk across the land with the given behaviour
+    /// once the landmass has been generated, eg. to raise mountain ridges or dig lakes.
+    pub fn add_stepper(&mut self, behaviour: Box<dyn StepperBehaviour>, steppers: u32, steps: u32) -> &mut Self {
+        self.features.push((behaviour, steppers, steps));
 
         self
     }
 
-    fn run(&mut self) {
-        self.generate_landmass();
-
-        // post process the raw map
-        self.post_proccess();
+    pub fn generate(&mut self) -> Result<&mut Self, GeneratorError> {
+        self.run()?;
 
-        // flood fill with salt water
-        let mut ignored_tiles: Vec<MapPosition> = Vec::new();
-        let void = Biomes::Void.get_name();
+        Ok(self)
+    }
 
-        for x in 0..self.map_size {
-            for y in 0..self.map_size {
-                if self.map_data[x as usize][y as usize].get_tile_name() != void {
-                    ignored_tiles.push(MapPosition {x: x as i32, y: y as i32});
+    /// Generates maps until one meets the constraints, each attempt with a seed derived from the seed of the
+    /// generator. While the land ratio is out of range the number of steppers is scaled towards it too.
+    ///
+    /// The report tells which attempt succeeded, and the generator keeps its seed and steppers, so generating with
+    /// them again gives the same map. An attempt whose map has no land or no sea counts as not meeting them. When no
+    /// attempt succeeds, or the generator is set up wrong, the generator gets its own seed and steppers back.
+    pub fn generate_with_constraints(&mut self, constraints: &Constraints) -> Result<ConstraintReport, GeneratorError> {
+        constraints.validate()?;
+
+        let (seed, steppers) = (self.seed.clone(), self.steppers);
+        let mut violations = Vec::new();
+
+        for attempt in 1..=constraints.get_max_attempts() {
+            self.seed = attempt_seed(&seed, attempt);
+
+            violations = match self.run() {
+                Ok(()) => constraints.check(&self.map_data),
+                // another seed may well give a usable map, so a map without land or sea only fails this attempt
+                Err(GeneratorError::DegenerateMap(message)) => vec![message],
+                Err(err) => {
+                    self.seed = seed;
+                    self.steppers = steppers;
+
+                    return Err(err);
                 }
+            };
+
+            if violations.is_empty() {
+                return Ok(ConstraintReport {
+                    attempt,
+                    seed: self.seed.clone(),
+                    steppers: self.steppers.unwrap_or_default(),
+                });
+            }
+
+            if let (Some(scale), Some(current)) = (constraints.stepper_scale(&self.map_data), self.steppers) {
+                self.steppers = Some(((current as f32 * scale).round() as u32).max(1));
             }
         }
 
-        self.flood_fill(Biomes::Void, Biomes::SaltWater, 0, 0, &mut ignored_tiles);
+        self.seed = seed;
+        self.steppers = steppers;
+
+        Err(GeneratorError::UnmetConstraints(format!(
+            "no map met them in {} attempts, the last one had {}",
+            constraints.get_max_attempts(),
+            violations.join(", ")
+        )))
+    }
+
+    fn run(&mut self) -> Result<(), GeneratorError> {
+        validate_layout(self.width, self.height, self.get_shape(), self.get_topology(), self.equator.unwrap_or(0.5))?;
+
+        // start from a blank map, so generating twice with the same settings gives the same map
+        self.map_data = Grid::new(self.width, self.height, Biome::new_empty())
+            .with_topology(self.get_topology())
+            .with_shape(self.get_shape());
+        self.rng = Seeder::from(&self.seed).make_rng();
+        self.river_paths.clear();
+
+        self.generate_landmass()?;
+
+        // let any additional steppers shape the land
+        self.generate_features();
+
+        // post process the raw map
+        self.post_proccess();
+
+        // flood fill with salt water
+        let ignored_tiles: Vec<MapPosition> = self.map_data
+            .enumerate()
+            .filter(|(_, tile)| tile.tile_type != Biomes::Void)
+            .map(|(position, _)| position)
+            .collect();
+
+        // the sea reaches every edge which does not wrap, without any such edge it is the largest body of water
+        let topology = self.get_topology();
+        let sea: Vec<MapPosition> = if topology.wraps_y() {
+            self.find_largest_region(Biomes::Void).into_iter().collect()
+        } else {
+            let (width, height) = (self.width as i32, self.height as i32);
+
+            self.map_data
+                .positions()
+                .filter(|position| {
+                    position.y == 0 || position.y == height - 1
+                        || (!topology.wraps_x() && (position.x == 0 || position.x == width - 1))
+                })
+                .collect()
+        };
+
+        self.flood_fill(Biomes::Void, Biomes::SaltWater, &sea, &ignored_tiles);
 
         // Replace last void tiles with fresh water
         self.find_replace(Biomes::Void, Biomes::FreshWater, true);
         self.find_replace(Biomes::Void, Biomes::Placeholder, false);
 
         // generate elevation
-        self.generate_elevation();
+        self.generate_elevation()?;
      
         // create rivers
-        // self._generate_rivers();
+        self.generate_rivers();
 
         // calculate the moisture for each placeholder cell
         self.generate_moisture(); 
 
+        // calculate the temperature from the latitude and height of each placeholder cell
+        self.generate_temperature();
+
         // generate beaches
         self.generate_beaches();        
 
         // generate biomes
-        for x in 0..self.map_size {
-            for y in 0..self.map_size {
-                if self.map_data[x as usize][y as usize].get_tile_name() != Biomes::Placeholder.get_name() {
-                    continue;
-                }
-
-                self.map_data[x as usize][y as usize].calculate_biome();
+        for tile in self.map_data.iter_mut() {
+            if tile.tile_type != Biomes::Placeholder {
+                continue;
             }
+
+            tile.calculate_biome(&self.biome_table);
         }
-    }
 
-    fn generate_landmass(&mut self) {
-        let land_stepper = Generators::LandGenerator;
-        let mut start_positions: Vec<MapPosition> = Vec::new();
-        let base_offset = self.map_size / 2;
-        let max_offset = base_offset / 2;
+        Ok(())
+    }
 
-        for _ in 0..self.rng.gen_range(1..=20) {
-            let x_offset = self.rng.gen_range((max_offset as i32 * -1)..=max_offset as i32);
-            let y_offset = self.rng.gen_range((max_offset as i32 * -1)..=max_offset as i32);
+    fn generate_landmass(&mut self) -> Result<(), GeneratorError> {
+        if self.land_mask.is_some() {
+            self.generate_masked_landmass();
 
-            start_positions.push(MapPosition {
-                x: base_offset as i32 + x_offset,
-                y: base_offset as i32 + y_offset
-            });
+            return Ok(());
         }
 
-        for index in 0..self.steppers.unwrap() {
+        let steppers = self.steppers
+            .ok_or_else(|| GeneratorError::InvalidConfig("the number of steppers is not set".to_string()))?;
+        let steps = self.steps
+            .ok_or_else(|| GeneratorError::InvalidConfig("the number of steps is not set".to_string()))?;
+        let land_stepper = match self.preset {
+            Some(preset) => Landmass::with_fill_size(preset.fill_size()),
+            None => Landmass::create(),
+        };
+        let start_positions = self.start_positions();
+
+        let (width, height, topology, shape) = (self.width, self.height, self.get_topology(), self.get_shape());
+        let stepper = |index: u32| {
             let mut seed = String::from(&self.seed);
             seed.push_str(&index.to_string());
             let mut rng: Pcg64 = Seeder::from(seed).make_rng();
 
             let position = start_positions[rng.gen_range(0..start_positions.len())];
 
-            let mut stepper = Stepper::create(
+            Stepper::create(
                 rng,
-                self.map_size,
-                self.steps.unwrap(),
+                width,
+                height,
+                steps,
                 position,
-            );
+            )
+        };
+
+        if !self.is_parallel() {
+            for index in 0..steppers {
+                stepper(index).run(&mut self.map_data, &land_stepper);
+            }
 
-            stepper.run(&mut self.map_data, land_stepper);
+            return Ok(());
         }
-    }
 
-    fn _generate_rivers(&self) {
-        unimplemented!();
+        // every thread walks its share of the steppers over a blank map of its own, the steppers only ever add
+        // land so the maps can be merged in any order
+        let indexes: Vec<u32> = (0..steppers).collect();
+        let per_thread = indexes.len().div_ceil(rayon::current_num_threads()).max(1);
+        let masks: Vec<MapData> = indexes
+            .par_chunks(per_thread)
+            .map(|indexes| {
+                let mut mask = Grid::new(width, height, Biome::new_empty())
+                    .with_topology(topology)
+                    .with_shape(shape);
+
+                for index in indexes {
+                    stepper(*index).run(&mut mask, &land_stepper);
+                }
+
+                mask
+            })
+            .collect();
+
+        for mask in masks {
+            for (tile, masked) in self.map_data.iter_mut().zip(mask.iter()) {
+                if masked.tile_type == Biomes::Placeholder {
+                    tile.tile_type = Biomes::Placeholder;
+                }
+            }
+        }
+
+        Ok(())
     }
 
-    fn generate_elevation(&mut self) {
-        let locations = self.find_tiles_near_type(Biomes::Placeholder, Biomes::SaltWater);
+    /// Picks the positions the landmass steppers start from, each stepper starts from one of them at random.
+    fn start_positions(&mut self) -> Vec<MapPosition> {
+        let layout = match self.preset {
+            Some(preset) => preset.start_layout(),
+            None => StartLayout::Centre { starts: 1..=20, spread: 0.5 },
+        };
+        let (x_base, y_base) = (self.width as i32 / 2, self.height as i32 / 2);
+        let mut start_positions: Vec<MapPosition> = Vec::new();
 
-        // normalise the distances by deviding the biggest distance by 4 (the heights elevation possible)
-        let per_elevation = locations.first().unwrap().2 / 4.0;
+        match layout {
+            StartLayout::Centre { starts, spread } => {
+                let (x_max_offset, y_max_offset) = ((x_base as f32 * spread) as i32, (y_base as f32 * spread) as i32);
 
-        for tile in locations {
-            let mut elevation = (tile.2 / per_elevation).floor();
+                for _ in 0..self.rng.gen_range(starts) {
+                    let x_offset = self.rng.gen_range(-x_max_offset..=x_max_offset);
+                    let y_offset = self.rng.gen_range(-y_max_offset..=y_max_offset);
 
-            if elevation < 1.0 {
-                elevation = 1.0;
+                    start_positions.push(MapPosition {
+                        x: x_base + x_offset,
+                        y: y_base + y_offset
+                    });
+                }
+            }
+            StartLayout::Scattered { starts } => {
+                // keep a tenth of the map clear along each edge, so the islands are not cut off by it
+                let (x_margin, y_margin) = (self.width as i32 / 10, self.height as i32 / 10);
+
+                for _ in 0..self.rng.gen_range(starts) {
+                    start_positions.push(MapPosition {
+                        x: self.rng.gen_range(x_margin..=self.width as i32 - 1 - x_margin),
+                        y: self.rng.gen_range(y_margin..=self.height as i32 - 1 - y_margin),
+                    });
+                }
             }
+            StartLayout::Ring { starts, radius } => {
+                let count = self.rng.gen_range(starts);
+                let turn = self.rng.gen_range(0.0..std::f64::consts::TAU);
 
-            self.map_data[tile.0 as usize][tile.1 as usize].distance_from_sea = tile.2 as u32;
-            self.map_data[tile.0 as usize][tile.1 as usize].elevation = elevation as u32;
+                for index in 0..count {
+                    let angle = turn + std::f64::consts::TAU * index as f64 / count as f64;
+
+                    start_positions.push(MapPosition {
+                        x: x_base + (angle.cos() * x_base as f64 * radius as f64).round() as i32,
+                        y: y_base + (angle.sin() * y_base as f64 * radius as f64).round() as i32,
+                    });
+                }
+            }
         }
+
+        start_positions
     }
 
-    fn generate_moisture(&mut self) {
-        let locations = self.find_tiles_near_type(Biomes::Placeholder, Biomes::FreshWater);
+    /// Paints the land of the land mask, then lets the coast steppers walk inland and out to sea from random
+    /// points along its coast so it does not follow the mask pixel for pixel.
+    fn generate_masked_landmass(&mut self) {
+        let Some(mask) = &self.land_mask else {
+            return;
+        };
+        let (width, height) = (self.width, self.height);
 
-        // normalise the distances by deviding the biggest distance by 6 (the height level of moisture)
-        let per_moisture_stage = locations.first().unwrap().2 / 6.0;
+        for position in self.map_data.positions() {
+            if mask.is_land(position, width, height) {
+                self.map_data[position].tile_type = Biomes::Placeholder;
+            }
+        }
+
+        let coast: Vec<MapPosition> = self.map_data
+            .positions()
+            .filter(|position| self.map_data[*position].tile_type == Biomes::Placeholder)
+            .filter(|position| {
+                self.map_data
+                    .adjacent(*position)
+                    .any(|neighbour| self.map_data[neighbour].tile_type != Biomes::Placeholder)
+            })
+            .collect();
+
+        if coast.is_empty() {
+            return;
+        }
+
+        let land = Landmass::create();
+        let erosion = Erosion::create(EROSION_RADIUS);
+        let steps = self.coast_steps.unwrap_or(30);
 
-        for tile in locations {
-            let mut moisture = 6 - (tile.2 / per_moisture_stage).ceil() as u32 + 1;
+        for index in 0..self.coast_steppers.unwrap_or(0) {
+            let mut seed = String::from(&self.seed);
+            seed.push_str("coast");
+            seed.push_str(&index.to_string());
+            let mut rng: Pcg64 = Seeder::from(seed).make_rng();
+
+            let position = coast[rng.gen_range(0..coast.len())];
+            let behaviour: &dyn StepperBehaviour = if index % 2 == 0 { &land } else { &erosion };
+
+            Stepper::create(rng, width, height, steps, position).run(&mut self.map_data, behaviour);
+        }
+    }
+
+    fn generate_features(&mut self) {
+        let land: Vec<MapPosition> = self.map_data
+            .enumerate()
+            .filter(|(_, tile)| tile.tile_type == Biomes::Placeholder)
+            .map(|(position, _)| position)
+            .collect();
+
+        if land.is_empty() {
+            return;
+        }
+
+        for (feature, (behaviour, steppers, steps)) in self.features.iter().enumerate() {
+            for index in 0..*steppers {
+                let seed = format!("{}feature{}_{}", self.seed, feature, index);
+                let mut rng: Pcg64 = Seeder::from(seed).make_rng();
 
-            if moisture < 1 {
-                moisture = 1;
+                let position = land[rng.gen_range(0..land.len())];
+
+                let mut stepper = Stepper::create(
+                    rng,
+                    self.width,
+                    self.height,
+                    *steps,
+                    position,
+                );
+
+                stepper.run(&mut self.map_data, behaviour.as_ref());
             }
+        }
+    }
+
+    fn generate_rivers(&mut self) {
+        let rivers = self.rivers.unwrap_or(0);
 
-            self.map_data[tile.0 as usize][tile.1 as usize].distance_from_fresh_water = tile.2 as u32;
-            self.map_data[tile.0 as usize][tile.1 as usize].moisture = moisture as u32;
+        if rivers == 0 {
+            return;
+        }
+
+        // rivers spring from the highest land available on the map
+        let mut sources: Vec<MapPosition> = Vec::new();
+        let mut highest = 0;
+
+        for (position, tile) in self.map_data.enumerate() {
+            if tile.tile_type != Biomes::Placeholder || tile.elevation < highest {
+                continue;
+            }
+
+            if tile.elevation > highest {
+                highest = tile.elevation;
+                sources.clear();
+            }
+
+            sources.push(position);
+        }
+
+        let mut created = 0;
+
+        while created < rivers && !sources.is_empty() {
+            let source = sources.swap_remove(self.rng.gen_range(0..sources.len()));
+
+            // an earlier river may already have claimed or run past this tile
+            if self.map_data[source].tile_type != Biomes::Placeholder
+                || !self.get_tile_neighbours(&source, &Biomes::FreshWater, true).is_empty()
+            {
+                continue;
+            }
+
+            self.generate_river(source);
+            created += 1;
         }
     }
 
-    fn find_tiles_near_type(&mut self, find_biome: Biomes, near_biome: Biomes) -> Vec<(u32, u32, f32)> {
-        let mut locations: Vec<(u32, u32, f32)> = Vec::new();
-        let biome_type = find_biome.get_name();
+    fn generate_river(&mut self, source: MapPosition) {
+        let mut path: Vec<MapPosition> = vec![source];
+        let mut visited: HashSet<MapPosition> = HashSet::from([source]);
+        let mut current = source;
+
+        loop {
+            let neighbours = self.get_tile_neighbours(&current, &Biomes::SaltWater, false)
+                .into_iter()
+                .chain(self.get_tile_neighbours(&current, &Biomes::FreshWater, false))
+                .filter(|position| !visited.contains(position))
+                .count();
+
+            // the river has reached the sea, a lake or another river
+            if neighbours > 0 {
+                break;
+            }
+
+            // flow towards the lowest neighbour, but never uphill
+            let mut lowest: Vec<MapPosition> = Vec::new();
+            let mut lowest_height = self.map_data[current].height;
 
-        for x in 0..self.map_size {
-            for y in 0..self.map_size {
-                if self.map_data[x as usize][y as usize].get_tile_name() != biome_type {
+            for position in self.get_tile_neighbours(&current, &Biomes::Placeholder, false) {
+                if visited.contains(&position) {
                     continue;
                 }
 
-                let nearest = self.find_nearest(x, y, near_biome);
+                let neighbour_height = self.map_data[position].height;
 
-                if nearest.is_none() {
+                if neighbour_height > lowest_height {
                     continue;
                 }
 
-                locations.push((x, y, nearest.unwrap().2));
+                if neighbour_height < lowest_height {
+                    lowest_height = neighbour_height;
+                    lowest.clear();
+                }
+
+                lowest.push(position);
             }
+
+            // nowhere lower left to flow, so the river ends here in a sink
+            if lowest.is_empty() {
+                break;
+            }
+
+            current = lowest[self.rng.gen_range(0..lowest.len())];
+            visited.insert(current);
+            path.push(current);
         }
 
-        // // sort by distance
-        locations.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
+        for position in &path {
+            self.map_data[*position].tile_type = Biomes::FreshWater;
+        }
 
-        return locations;
+        self.river_paths.push(path);
     }
 
-    fn generate_beaches(&mut self) {
-        for x in 0..self.map_size {
-            for y in 0..self.map_size {
-                let tile = &self.map_data[x as usize][y as usize];
+    fn generate_elevation(&mut self) -> Result<(), GeneratorError> {
+        let distances = self.find_tiles_near_type(Biomes::Placeholder, Biomes::SaltWater);
 
-                if tile.get_tile_name() != Biomes::Placeholder.get_name() {
-                    continue;
+        if !distances.iter().any(|distance| distance.is_finite()) {
+            let land = self.map_data.iter()
+                .any(|tile| tile.tile_type == Biomes::Placeholder);
+
+            return Err(GeneratorError::DegenerateMap(
+                if land { "the map contains no sea" } else { "the map contains no land" }.to_string()
+            ));
+        }
+
+        let max_distance = Self::max_distance(&distances);
+        let noise = Noise::new(&mut self.rng);
+        let (topology, shape) = (self.get_topology(), self.get_shape());
+        let frequency = NOISE_FEATURES / self.width as f64;
+        // a wrapping axis needs a whole number of noise features across it, so the noise lines up at the seam
+        let y_features = (NOISE_FEATURES * self.height as f64 / self.width as f64).round().max(1.0);
+        let y_frequency = if topology.wraps_y() { y_features / self.height as f64 } else { frequency };
+        let x_period = topology.wraps_x().then_some(NOISE_FEATURES as u32);
+        let y_period = topology.wraps_y().then_some(y_features as u32);
+        let parallel = self.is_parallel();
+
+        let max_height = self.map_data
+            .map_rows_mut(parallel, |y, row| {
+                let mut max_height: f32 = 0.0;
+
+                for (x, (tile, distance)) in row.iter_mut().zip(distances.row(y)).enumerate() {
+                    if distance.is_infinite() {
+                        continue;
+                    }
+
+                    // the distance to the coast keeps the shores low and pulls the land up towards the interior
+                    let falloff = distance / max_distance;
+                    let (centre_x, centre_y) = shape.centre(MapPosition { x: x as i32, y: y as i32 });
+                    let sample = noise.fractal_periodic(
+                        centre_x * frequency,
+                        centre_y * y_frequency,
+                        NOISE_OCTAVES,
+                        x_period,
+                        y_period,
+                    ) as f32;
+                    let terrain = ((sample * NOISE_CONTRAST + 1.0) / 2.0).clamp(0.0, 1.0);
+                    // anything the steppers have already raised, like ridges, is added on top of the terrain
+                    let height = (falloff / COAST_RAMP).min(1.0) * (tile.height + falloff * COAST_WEIGHT + terrain * (1.0 - COAST_WEIGHT));
+
+                    max_height = max_height.max(height);
+
+                    tile.height = height;
+                    tile.distance_from_sea = *distance as u32;
                 }
 
-                if tile.elevation != 1 || tile.moisture > 2 {
+                max_height
+            })
+            .into_iter()
+            .fold(0.0, f32::max);
+
+        // normalise the heights so the highest peak on the map always sits at 1.0
+        self.map_data.map_rows_mut(parallel, |y, row| {
+            for (tile, distance) in row.iter_mut().zip(distances.row(y)) {
+                if distance.is_infinite() {
                     continue;
                 }
 
-                let neighbours = self.get_tile_neighbours(
-                    &MapPosition { x: x as i32, y: y as i32 },
-                    &Biomes::SaltWater,
-                    true
-                );
+                tile.height = if max_height > 0.0 { tile.height / max_height } else { 0.0 };
+                tile.elevation = Biome::elevation_band(tile.height);
+            }
+        });
+
+        Ok(())
+    }
+
+    fn generate_temperature(&mut self) {
+        let equator = self.equator.unwrap_or(0.5).clamp(0.0, 1.0) * (self.height - 1) as f32;
+        let wraps = self.get_topology().wraps_y();
+        // the furthest row from the equator is the pole, on a map which wraps north to south it is half the map away
+        let pole_distance = if wraps {
+            (self.height as f32 / 2.0).max(1.0)
+        } else {
+            equator.max((self.height - 1) as f32 - equator).max(1.0)
+        };
+        let ocean_influence = (self.width.max(self.height) as f32 * OCEAN_INFLUENCE).max(1.0);
+
+        for (y, row) in self.map_data.rows_mut().enumerate() {
+            let mut equator_distance = (y as f32 - equator).abs();
+
+            if wraps {
+                equator_distance = equator_distance.min(self.height as f32 - equator_distance);
+            }
+
+            let latitude = equator_distance / pole_distance;
 
-                if neighbours.len() >= 1 {
-                    self.map_data[x as usize][y as usize].tile_type = Biomes::Beach;
+            for tile in row.iter_mut() {
+                if tile.tile_type != Biomes::Placeholder {
+                    continue;
                 }
+
+                let mut temperature = 1.0 - latitude * LATITUDE_COOLING - tile.height * LAPSE_RATE;
+
+                // the sea evens out the temperature of the land close to it
+                let coastal = 1.0 - (tile.distance_from_sea as f32 / ocean_influence).min(1.0);
+                temperature += (OCEAN_TEMPERATURE - temperature) * coastal * OCEAN_MODERATION;
+
+                tile.temperature = temperature.clamp(0.0, 1.0);
             }
         }
     }
 
-    fn find_nearest(&mut self, x_origin: u32, y_origin: u32, find_biome: Biomes) -> Option<(u32, u32, f32)> {
-        let type_name = find_biome.get_name();
-        let mut closest: Vec<(u32, u32, f32)> = Vec::new();
-        let mut x_start = x_origin as i32;
-        let mut x_max = x_origin as i32;
-        let mut y_start = y_origin as i32;
-        let mut y_max = y_origin as i32;
-        let map_size_i32 = self.map_size as i32;
+    fn generate_moisture(&mut self) {
+        let distances = self.find_tiles_near_type(Biomes::Placeholder, Biomes::FreshWater);
 
-        while closest.is_empty() {
-            x_start -= 1;
-            y_start -= 1;
-            x_max += 1;
-            y_max += 1;
+        // normalise the distances by deviding the biggest distance by 6 (the height level of moisture)
+        let per_moisture_stage = Self::max_distance(&distances) / 6.0;
 
-            if x_start < 0 && y_start < 0 && x_max >= map_size_i32 && y_max >= map_size_i32 {
-                break;
+        self.map_data.map_rows_mut(self.is_parallel(), |y, row| {
+            for (tile, distance) in row.iter_mut().zip(distances.row(y)) {
+                if distance.is_infinite() {
+                    continue;
+                }
+
+                let mut moisture = 6 - (distance / per_moisture_stage).ceil() as u32 + 1;
+
+                if moisture < 1 {
+                    moisture = 1;
+                }
+
+                tile.distance_from_fresh_water = *distance as u32;
+                tile.moisture = moisture;
             }
+        });
+    }
 
-            // let mut nx = x_start;
-            // this prevents it from looping over coordinates which are not within the vector
-            let optimised_x_start  = if x_max > map_size_i32 { map_size_i32 } else { x_max };
-            let mut nx = if x_start < 0 { 0 } else { x_start };
-            let optimised_x_max = if x_max > map_size_i32 { map_size_i32 } else { x_max };
+    /// The distance from every `find_biome` tile to the nearest `near_biome` tile. Every other tile is infinitely
+    /// far away, as is every tile when there is no `near_biome` tile on the map at all.
+    fn find_tiles_near_type(&self, find_biome: Biomes, near_biome: Biomes) -> Grid<f32> {
+        let mut distances = distance_field(&self.map_data, near_biome);
 
-            while nx <= optimised_x_max {
-                nx += 1;
+        for (distance, tile) in distances.iter_mut().zip(self.map_data.iter()) {
+            if tile.tile_type != find_biome {
+                *distance = f32::INFINITY;
+            }
+        }
 
-                // let mut ny = y_start;
-                // this prevents it from looping over coordinates which are not within the vector
-                let optimised_y_start  = if y_start < 0 { 0 } else { y_start };
-                let mut ny  = if y_start < 0 { 0 } else { y_start };
-                let optimised_y_max = if y_max > map_size_i32 { map_size_i32 } else { y_max };
+        distances
+    }
 
-                while ny <= optimised_y_max {
-                    ny += 1;
+    fn max_distance(distances: &Grid<f32>) -> f32 {
+        distances.iter()
+            .filter(|distance| distance.is_finite())
+            .fold(0.0, |max: f32, distance| max.max(*distance))
+    }
 
-                    // ignore tiles which we have already checked
-                    if ny > optimised_y_start && ny < y_max && nx > optimised_x_start && nx < x_max {
-                        continue;
-                    }
+    fn is_parallel(&self) -> bool {
+        self.parallel.unwrap_or(true)
+    }
 
-                    if !is_valid_cell(&self.map_size, nx, ny) {
-                        continue;
-                    }
+    /// A tile in the largest connected area of the given biome.
+    fn find_largest_region(&self, biome: Biomes) -> Option<MapPosition> {
+        let mut visited = Grid::new(self.map_data.width(), self.map_data.height(), false);
+        let mut largest: Option<(MapPosition, usize)> = None;
 
-                    if type_name != self.map_data[nx as usize][ny as usize].get_tile_name() {
-                        continue;
-                    }
+        for start in self.map_data.positions() {
+            if visited[start] || self.map_data[start].tile_type != biome {
+                continue;
+            }
+
+            let mut queue = VecDeque::from([start]);
+            let mut size = 0;
+
+            visited[start] = true;
+
+            while let Some(position) = queue.pop_front() {
+                size += 1;
 
-                    closest.push((
-                        nx as u32,
-                        ny as u32,
-                        get_distance(x_origin, y_origin, nx as u32, ny as u32)
-                    ));
+                for neighbour in self.map_data.adjacent(position) {
+                    if !visited[neighbour] && self.map_data[neighbour].tile_type == biome {
+                        visited[neighbour] = true;
+                        queue.push_back(neighbour);
+                    }
                 }
             }
+
+            if largest.is_none_or(|(_, largest_size)| size > largest_size) {
+                largest = Some((start, size));
+            }
         }
 
-        closest.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
+        largest.map(|(position, _)| position)
+    }
 
-        return closest.first().copied();
+    fn generate_beaches(&mut self) {
+        for position in self.map_data.positions() {
+            let tile = &self.map_data[position];
+
+            if tile.tile_type != Biomes::Placeholder {
+                continue;
+            }
+
+            if tile.elevation != 1 || tile.moisture > 2 {
+                continue;
+            }
+
+            let neighbours = self.get_tile_neighbours(&position, &Biomes::SaltWater, true);
+
+            if !neighbours.is_empty() {
+                self.map_data[position].tile_type = Biomes::Beach;
+            }
+        }
     }
 
     fn find_replace(&mut self, find: Biomes, replace: Biomes, ignore_solo_tiles: bool) {
-        let find_type = find.get_name();
-        let mut replacements: Vec<(usize, usize)> = vec![];
+        let mut replacements: Vec<MapPosition> = vec![];
 
-        for x in 0..self.map_size {
-            for y in 0..self.map_size {
-                if self.map_data[x as usize][y as usize].get_tile_name() != find_type {
-                    continue;
-                }
+        for (position, tile) in self.map_data.enumerate() {
+            if tile.tile_type != find {
+                continue;
+            }
 
-                if ignore_solo_tiles {
-                    let neighbours: Vec<MapPosition> = self.get_tile_neighbours(
-                        &MapPosition { x: x as i32, y: y as i32 },
-                        &self.map_data[x as usize][y as usize].tile_type,
-                        false
-                    );
+            if ignore_solo_tiles {
+                let neighbours: Vec<MapPosition> = self.get_tile_neighbours(&position, &tile.tile_type, false);
 
-                    if neighbours.len() == 0 {
-                        continue;
-                    }
+                if neighbours.is_empty() {
+                    continue;
                 }
-
-                replacements.push((x as usize, y as usize));
             }
+
+            replacements.push(position);
         }
 
-        for tile in replacements {
-            self.map_data[tile.0][tile.1].tile_type = replace;
+        for position in replacements {
+            self.map_data[position].tile_type = replace;
         }
     }
 
-    fn flood_fill(&mut self, fill_biome: Biomes, with_biome: Biomes, start_row: i32, start_col: i32, ignore: &Vec<MapPosition>) {
-        let rows = self.map_size as i32;
-        let cols = self.map_size as i32;
+    fn flood_fill(&mut self, fill_biome: Biomes, with_biome: Biomes, start: &[MapPosition], ignore: &[MapPosition]) {
         let mut queue = VecDeque::new();
-        let mut visited = vec![vec![false; cols as usize]; rows as usize];
+        let mut visited = Grid::new(self.map_data.width(), self.map_data.height(), false);
 
         for pos in ignore {
-            visited[pos.x as usize][pos.y as usize] = true;
+            visited[*pos] = true;
         }
 
-        queue.push_back((start_row, start_col));
+        queue.extend(start);
 
-        while let Some((row, col)) = queue.pop_front() {
-            if row >= 0 && row < rows && col >= 0 && col < cols && !visited[row as usize][col as usize] {
-                visited[row as usize][col as usize] = true;
-
-                if self.map_data[row as usize][col as usize].get_tile_name() != fill_biome.get_name() {
-                    continue;
-                }
+        while let Some(position) = queue.pop_front() {
+            if !self.map_data.contains(position) || visited[position] {
+                continue;
+            }
 
-                self.map_data[row as usize][col as usize].tile_type = with_biome;
+            visited[position] = true;
 
-                queue.push_back((row + 1, col));
-                queue.push_back((row - 1, col));
-                queue.push_back((row, col + 1));
-                queue.push_back((row, col - 1));
+            if self.map_data[position].tile_type != fill_biome {
+                continue;
             }
+
+            self.map_data[position].tile_type = with_biome;
+
+            queue.extend(self.map_data.adjacent(position));
         }
     }
 
@@ -370,206 +973,213 @@ impl Generator {
         // remove long stragglers
         self.remove_stragglers();
 
-        for x in 0..self.map_size {
-            for y in 0..self.map_size {
-                let tile = self.map_data[x as usize][y as usize].tile_type;
-                self.clean_tile(&tile, &MapPosition {x: x as i32, y: y as i32}, false);
-            }
+        for position in self.map_data.positions() {
+            self.clean_tile(position);
         }
     }
 
     fn remove_stragglers(&mut self) {
-        for x in 0..self.map_size {
-            for y in 0..self.map_size {
-                let tile = self.map_data[x as usize][y as usize].tile_type;
+        for current_pos in self.map_data.positions() {
+            let tile = self.map_data[current_pos].tile_type;
 
-                // only remove land placeholders
-                if let Biomes::Placeholder = tile {
-                    let current_pos = MapPosition {
-                        x: x as i32,
-                        y: y as i32
-                    };
+            // only remove land placeholders
+            if tile == Biomes::Placeholder {
+                let neighbours: Vec<MapPosition> = self.get_tile_neighbours(&current_pos, &tile, true);
 
-                    let neighbours: Vec<MapPosition> = self.get_tile_neighbours(&current_pos, &tile, true);
-
-                    if neighbours.len() <= 2 {
-                        self.map_data[x as usize][y as usize].tile_type = Biomes::Void;
-                    }
+                if neighbours.len() <= 2 {
+                    self.map_data[current_pos].tile_type = Biomes::Void;
                 }
             }
         }
     }
 
-    fn clean_tile(&mut self, tile: &Biomes, position: &MapPosition, skip: bool) {
-        if skip {
-            return;
-        }
-
-        // ignore outter rim
-        if position.y == 0 // top
-            || position.y as u32 == self.map_size - 1 // bottom
-            || position.x == 0 // left
-            || position.x as u32 == self.map_size - 1 // right
-         {
-            self.map_data[position.x_usize()][position.y_usize()].tile_type = Biomes::Void;
-            return;
-        }
+    /// Flips tiles which touch at most one tile of their own type, and keeps following the chain of tiles this
+    /// leaves behind, the rim of the map is always turned to void along the edges which do not wrap.
+    fn clean_tile(&mut self, position: MapPosition) {
+        let topology = self.get_topology();
+        let mut worklist: Vec<MapPosition> = vec![position];
+
+        while let Some(position) = worklist.pop() {
+            // ignore outter rim
+            let on_rim_y = position.y == 0 // top
+                || position.y as u32 == self.height - 1; // bottom
+            let on_rim_x = position.x == 0 // left
+                || position.x as u32 == self.width - 1; // right
+
+            if (on_rim_y && !topology.wraps_y()) || (on_rim_x && !topology.wraps_x()) {
+                self.map_data[position].tile_type = Biomes::Void;
+                continue;
+            }
 
-        let neighbours = self.get_tile_neighbours(position, tile, false);
+            let tile = self.map_data[position].tile_type;
+            let neighbours = self.get_tile_neighbours(&position, &tile, false);
 
-        if neighbours.len() > 1 {
-            return;
-        }
+            if neighbours.len() > 1 {
+                continue;
+            }
 
-        // if a tile is alone, convert it
-        if neighbours.len() < 2 {
-            self.map_data[position.x_usize()][position.y_usize()].tile_type = match tile {
+            // if a tile is alone, convert it
+            self.map_data[position].tile_type = match tile {
                 Biomes::Void => Biomes::Placeholder,
                 Biomes::FreshWater => Biomes::Placeholder,
                 _ => Biomes::Void,
             };
-        }
-
-        neighbours.iter().for_each(|position| {
-            let tile = self.map_data[position.x_usize()][position.y_usize()].tile_type;
 
-            self.clean_tile(&tile, &position, false)
-        });
+            worklist.extend(neighbours);
+        }
     }
 
     fn get_tile_neighbours(&self, position: &MapPosition, biome: &Biomes, cross_direction: bool) -> Vec<MapPosition> {
-        let mut neighbours: Vec<MapPosition> = Vec::new();
-        let mut directions = Direction::get_standard_directions();
-
-        if cross_direction {
-            directions = Direction::get_extended_directions();
-        }
-
-        directions
-            .iter_mut()
-            .for_each(|direction| {
-                let n_x = position.x + direction.x;
-                let n_y = position.y + direction.y;
-
-                if is_valid_cell(&self.map_size, n_x, n_y) {
-                    if self.map_data[n_x as usize][n_y as usize].tile_type.get_name() == biome.get_name() {
-                        neighbours.push(MapPosition { x: n_x, y: n_y });
-                    }
-                }
-            });
+        let neighbours: Vec<MapPosition> = if cross_direction {
+            self.map_data.surrounding(*position).collect()
+        } else {
+            self.map_data.adjacent(*position).collect()
+        };
 
-        return neighbours;
+        neighbours
+            .into_iter()
+            .filter(|neighbour| self.map_data[*neighbour].tile_type == *biome)
+            .collect()
     }
 
-    pub fn output_file(&mut self, file_name: String) -> &mut Self{
+    pub fn output_file(&mut self, file_name: String) -> Result<&mut Self, GeneratorError> {
         let path = Path::new(&file_name);
-        let display = path.display();
-
-        let mut file = match File::create(&path) {
-            Ok(file) => file,
-            Err(msg) => panic!("could not create {}: {}", display, msg),
-        };
+        let mut file = File::create(path)?;
 
-        file.write_all(format!("Seed: {}", &self.seed).as_bytes()).unwrap();
-        file.write_all("\n\nElevation:\n".as_bytes()).unwrap();
+        file.write_all(format!("Seed: {}", &self.seed).as_bytes())?;
+        file.write_all("\n\nElevation:\n".as_bytes())?;
 
         // render map
-        for col in self.map_data.iter() {
-            for tile in col.iter() {
-                file.write_all(tile.elevation.to_string().as_bytes()).unwrap();
+        for row in self.map_data.rows() {
+            for tile in row.iter() {
+                file.write_all(tile.elevation.to_string().as_bytes())?;
             }
 
-            file.write_all("\n".as_bytes()).unwrap();
+            file.write_all("\n".as_bytes())?;
         }
 
-        file.write_all("\n\nmoisture:\n".as_bytes()).unwrap();
+        file.write_all("\n\nmoisture:\n".as_bytes())?;
         // render map
-        for col in self.map_data.iter() {
-            for tile in col.iter() {
-                file.write_all(tile.moisture.to_string().as_bytes()).unwrap();
+        for row in self.map_data.rows() {
+            for tile in row.iter() {
+                file.write_all(tile.moisture.to_string().as_bytes())?;
             }
 
-            file.write_all("\n".as_bytes()).unwrap();
+            file.write_all("\n".as_bytes())?;
         }
 
-        file.write_all("\n\nsymbols:\n".as_bytes()).unwrap();
+        file.write_all("\n\nsymbols:\n".as_bytes())?;
         // render map
-        for col in self.map_data.iter() {
-            for tile in col.iter() {
-                file.write_all(tile.get_tile_symbol().as_bytes()).unwrap();
+        for row in self.map_data.rows() {
+            for tile in row.iter() {
+                file.write_all(self.biome_table.get_symbol(tile.tile_type).as_bytes())?;
             }
 
-            file.write_all("\n".as_bytes()).unwrap();
+            file.write_all("\n".as_bytes())?;
         }
 
-        file.write_all(format!("\n\nSeed: {}", &self.seed).as_bytes())
-            .unwrap();
+        file.write_all(format!("\n\nSeed: {}", &self.seed).as_bytes())?;
 
-        self
+        Ok(self)
     }
 
-    pub fn output_image(&mut self, file_name: String, draw_multiplier: u32) -> &mut Self {
-        let debug_multiplier = if self.debug.is_some() && self.debug.unwrap() { 3 } else { 1 };
-        let mut image: RgbImage = ImageBuffer::new(self.map_size * draw_multiplier, (self.map_size * draw_multiplier) * debug_multiplier);
-        let mut offset = 0;
-
-        // render map
-        for (x, col) in self.map_data.iter().enumerate() {
-            for (y, tile) in col.iter().enumerate() {
-                let tile_colour = tile.get_tile_colour();
+    pub fn output_map(&mut self, file_name: String, format: MapFormat) -> Result<&mut Self, GeneratorError> {
+        let file = File::create(Path::new(&file_name))?;
+        self.save(BufWriter::new(file), format)?;
 
-                for x_step in 0..draw_multiplier {
-                    for y_step in 0..draw_multiplier {
-                        let my = y as u32 * draw_multiplier + y_step;
-                        let mx = x as u32 * draw_multiplier + x_step;
+        Ok(self)
+    }
 
-                        image.put_pixel(mx as u32, my as u32, tile_colour);
-                    }
-                }
-            }
+    /// Writes the map in the given format, `scale` is the pixels per tile for the formats which render tiles.
+    pub fn output(&mut self, file_name: String, format: OutputFormat, scale: u32) -> Result<&mut Self, GeneratorError> {
+        match format {
+            OutputFormat::Png => self.output_image(file_name, scale),
+            OutputFormat::Txt => self.output_file(file_name),
+            OutputFormat::Json => self.output_map(file_name, MapFormat::Json),
+            OutputFormat::Binary => self.output_map(file_name, MapFormat::Binary),
+            OutputFormat::Tmx => self.output_tiled(file_name, scale),
         }
+    }
+
+    /// Exports the map for the Tiled map editor, see [`export_tiled`].
+    pub fn output_tiled(&mut self, file_name: String, tile_size: u32) -> Result<&mut Self, GeneratorError> {
+        export_tiled(&self.map_data, &self.biome_table, Path::new(&file_name), tile_size)?;
+
+        Ok(self)
+    }
+
+    pub fn output_image(&mut self, file_name: String, draw_multiplier: u32) -> Result<&mut Self, GeneratorError> {
+        let table = &self.biome_table;
+        let tile_colour = |tile: &Biome| table.get_colour(tile.tile_type);
+        let elevation_colour = |tile: &Biome| tile.get_elevation_colour(table);
+        let temperature_colour = |tile: &Biome| tile.get_temperature_colour(table);
+        let mut panels: Vec<PanelColour> = vec![&tile_colour];
 
         if self.debug.is_some() && self.debug.unwrap() {
-            offset += self.map_size * draw_multiplier;
+            panels.extend([&Biome::get_moisture_colour as PanelColour, &elevation_colour, &temperature_colour]);
+        }
 
-            // render map
-            for (x, col) in self.map_data.iter().enumerate() {
-                for (y, tile) in col.iter().enumerate() {
-                    let moisture = tile.get_moisture_colour();
+        let (panel_width, panel_height) = match self.get_shape() {
+            Shape::Square => (self.width * draw_multiplier, self.height * draw_multiplier),
+            // a hex is two draw multipliers across, so it covers about as many pixels as a square tile
+            Shape::Hex => hex_panel_size(&self.map_data, draw_multiplier * 2),
+        };
+        let mut image: RgbImage = ImageBuffer::new(panel_width, panel_height * panels.len() as u32);
 
-                    for x_step in 0..draw_multiplier {
-                        for y_step in 0..draw_multiplier {
-                            let my = y as u32 * draw_multiplier + y_step + offset;
-                            let mx = x as u32 * draw_multiplier + x_step;
+        // render map, with each debugging layer stacked below it
+        for (index, colour) in panels.into_iter().enumerate() {
+            let offset = index as u32 * panel_height;
 
-                            image.put_pixel(mx as u32, my as u32, moisture);
-                        }
-                    }
-                }
+            if self.get_shape() == Shape::Hex {
+                draw_hex_panel(&mut image, &self.map_data, offset, draw_multiplier * 2, colour);
+                continue;
             }
 
-            offset += self.map_size * draw_multiplier;
+            for (position, tile) in self.map_data.enumerate() {
+                let tile_colour = colour(tile);
 
-            // render map
-            for (x, col) in self.map_data.iter().enumerate() {
-                for (y, tile) in col.iter().enumerate() {
-                    let elevation = tile.get_elevation_colour();
-
-                    for x_step in 0..draw_multiplier {
-                        for y_step in 0..draw_multiplier {
-                            let my = y as u32 * draw_multiplier + y_step + offset;
-                            let mx = x as u32 * draw_multiplier + x_step;
+                for x_step in 0..draw_multiplier {
+                    for y_step in 0..draw_multiplier {
+                        let my = position.y as u32 * draw_multiplier + y_step + offset;
+                        let mx = position.x as u32 * draw_multiplier + x_step;
 
-                            image.put_pixel(mx as u32, my as u32, elevation);
-                        }
+                        image.put_pixel(mx, my, tile_colour);
                     }
                 }
             }
         }
 
         // write it out to a file
-        image.save(&file_name).unwrap();
+        image.save(&file_name)?;
 
-        self
+        Ok(self)
     }
 }
+
+/// Checks the settings a map is laid out with, shared by [`GeneratorBuilder::build`] and every run of the generator
+/// so a map reconfigured with [`Generator::set`] is held to the same rules.
+pub(crate) fn validate_layout(
+    width: u32,
+    height: u32,
+    shape: Shape,
+    topology: Topology,
+    equator: f32,
+) -> Result<(), GeneratorError> {
+    if width < 3 || height < 3 {
+        return Err(GeneratorError::InvalidConfig(format!("the map must be at least 3x3, got {}x{}", width, height)));
+    }
+
+    // the rows of a hex map alternate between shifted and not, so they only line up across the seam in pairs
+    if shape == Shape::Hex && topology.wraps_y() && !height.is_multiple_of(2) {
+        return Err(GeneratorError::InvalidConfig(format!(
+            "a hex map which wraps north to south needs an even height, got {}",
+            height
+        )));
+    }
+
+    if !(0.0..=1.0).contains(&equator) {
+        return Err(GeneratorError::InvalidConfig(format!("the equator must be between 0.0 and 1.0, got {}", equator)));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,270 @@
+use crate::steppers::map_position::MapPosition;
+use crate::steppers::move_direction::MoveDirection;
+use crate::shape::Shape;
+use crate::topology::Topology;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ops::{Index, IndexMut};
+
+/// A 2D grid stored as a single row major buffer, `x` runs along the width and `y` along the height.
+///
+/// Positions past the edges of a wrapping [`Topology`] come back in on the opposite edge, and the [`Shape`] of
+/// the tiles decides which positions are next to each other.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawGrid<T>")]
+pub struct Grid<T> {
+    width: u32,
+    height: u32,
+    #[serde(default)]
+    topology: Topology,
+    #[serde(default)]
+    shape: Shape,
+    cells: Vec<T>,
+}
+
+// the unchecked shape of a grid as it comes out of a deserializer
+#[derive(Deserialize)]
+struct RawGrid<T> {
+    width: u32,
+    height: u32,
+    #[serde(default)]
+    topology: Topology,
+    #[serde(default)]
+    shape: Shape,
+    cells: Vec<T>,
+}
+
+impl<T> TryFrom<RawGrid<T>> for Grid<T> {
+    type Error = String;
+
+    fn try_from(raw: RawGrid<T>) -> Result<Self, Self::Error> {
+        let (width, height) = (raw.width, raw.height);
+
+        Grid::from_vec(width, height, raw.cells)
+            .map(|grid| grid.with_topology(raw.topology).with_shape(raw.shape))
+            .ok_or_else(|| format!("the cells do not fill a {}x{} grid", width, height))
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid with every cell set to `value`.
+    pub fn new(width: u32, height: u32, value: T) -> Self {
+        Self {
+            width,
+            height,
+            topology: Topology::Bounded,
+            shape: Shape::Square,
+            cells: vec![value; width as usize * height as usize],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Wraps a row major buffer, returns `None` when the buffer does not exactly fill the grid.
+    pub fn from_vec(width: u32, height: u32, cells: Vec<T>) -> Option<Self> {
+        if cells.len() != width as usize * height as usize {
+            return None;
+        }
+
+        Some(Self {
+            width,
+            height,
+            topology: Topology::Bounded,
+            shape: Shape::Square,
+            cells,
+        })
+    }
+
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    pub fn with_shape(mut self, shape: Shape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Whether the position is on the map, once it has been wrapped around the edges.
+    pub fn contains(&self, position: MapPosition) -> bool {
+        self.wrap(position).is_some()
+    }
+
+    /// Brings a position past a wrapping edge back onto the map, returns `None` when it is past an edge which
+    /// does not wrap.
+    pub fn wrap(&self, position: MapPosition) -> Option<MapPosition> {
+        let x = wrap_axis(position.x, self.width, self.topology.wraps_x())?;
+        let y = wrap_axis(position.y, self.height, self.topology.wraps_y())?;
+
+        Some(MapPosition { x, y })
+    }
+
+    /// The index of the position in the underlying buffer.
+    pub fn index_of(&self, position: MapPosition) -> Option<usize> {
+        let position = self.wrap(position)?;
+
+        Some(position.y as usize * self.width as usize + position.x as usize)
+    }
+
+    /// The position of an index in the underlying buffer.
+    pub fn position_of(&self, index: usize) -> MapPosition {
+        MapPosition {
+            x: (index % self.width as usize) as i32,
+            y: (index / self.width as usize) as i32,
+        }
+    }
+
+    pub fn get(&self, position: MapPosition) -> Option<&T> {
+        self.index_of(position).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, position: MapPosition) -> Option<&mut T> {
+        self.index_of(position).map(|index| &mut self.cells[index])
+    }
+
+    /// Every cell in row major order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    /// Every position in the grid in row major order, does not borrow the grid.
+    pub fn positions(&self) -> impl Iterator<Item = MapPosition> {
+        let (width, height) = (self.width as i32, self.height as i32);
+
+        (0..height).flat_map(move |y| (0..width).map(move |x| MapPosition { x, y }))
+    }
+
+    /// Every cell together with its position, in row major order.
+    pub fn enumerate(&self) -> impl Iterator<Item = (MapPosition, &T)> + '_ {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, y: u32) -> &[T] {
+        let start = y as usize * self.width as usize;
+
+        &self.cells[start..start + self.width as usize]
+    }
+
+    pub fn rows(&self) -> std::slice::ChunksExact<'_, T> {
+        self.cells.chunks_exact(self.width.max(1) as usize)
+    }
+
+    pub fn rows_mut(&mut self) -> std::slice::ChunksExactMut<'_, T> {
+        self.cells.chunks_exact_mut(self.width.max(1) as usize)
+    }
+
+    /// Calls `f` with every row and its `y`, spread across threads when `parallel` is set, and collects what it
+    /// returns in row order. Rows never share cells, so the result is the same either way.
+    pub fn map_rows_mut<R, F>(&mut self, parallel: bool, f: F) -> Vec<R>
+    where
+        T: Send,
+        R: Send,
+        F: Fn(u32, &mut [T]) -> R + Sync + Send,
+    {
+        let width = self.width.max(1) as usize;
+
+        if parallel {
+            self.cells.par_chunks_exact_mut(width).enumerate().map(|(y, row)| f(y as u32, row)).collect()
+        } else {
+            self.cells.chunks_exact_mut(width).enumerate().map(|(y, row)| f(y as u32, row)).collect()
+        }
+    }
+
+    pub fn column(&self, x: u32) -> impl Iterator<Item = &T> + '_ {
+        self.cells.iter().skip(x as usize).step_by(self.width.max(1) as usize)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// The tiles which share a side with `position`, see [`Shape::get_adjacent_directions`].
+    pub fn adjacent(&self, position: MapPosition) -> impl Iterator<Item = MapPosition> {
+        self.offsets(position, self.shape.get_adjacent_directions(position))
+    }
+
+    /// Every tile touching `position`, see [`Shape::get_surrounding_directions`].
+    pub fn surrounding(&self, position: MapPosition) -> impl Iterator<Item = MapPosition> {
+        self.offsets(position, self.shape.get_surrounding_directions(position))
+    }
+
+    fn offsets(&self, position: MapPosition, directions: Vec<MoveDirection>) -> impl Iterator<Item = MapPosition> {
+        let (width, height, topology) = (self.width, self.height, self.topology);
+
+        directions
+            .into_iter()
+            .filter_map(move |direction| {
+                let x = wrap_axis(position.x + direction.x, width, topology.wraps_x())?;
+                let y = wrap_axis(position.y + direction.y, height, topology.wraps_y())?;
+
+                Some(MapPosition { x, y })
+            })
+    }
+
+    /// Creates a grid of the same size by mapping every cell.
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            topology: self.topology,
+            shape: self.shape,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+fn wrap_axis(value: i32, size: u32, wraps: bool) -> Option<i32> {
+    if wraps && size > 0 {
+        return Some(value.rem_euclid(size as i32));
+    }
+
+    (value >= 0 && (value as u32) < size).then_some(value)
+}
+
+impl<T> Index<MapPosition> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: MapPosition) -> &Self::Output {
+        match self.index_of(position) {
+            Some(index) => &self.cells[index],
+            None => panic!("{:?} is outside of the {}x{} grid", position, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<MapPosition> for Grid<T> {
+    fn index_mut(&mut self, position: MapPosition) -> &mut Self::Output {
+        match self.index_of(position) {
+            Some(index) => &mut self.cells[index],
+            None => panic!("{:?} is outside of the {}x{} grid", position, self.width, self.height),
+        }
+    }
+}
@@ -1,18 +1,40 @@
-pub fn get_distance(from_x: u32, from_y: u32, to_x: u32, to_y: u32) -> f32{
-    let y = to_x as f32 - from_x as f32;
-    let x = to_y as f32 - from_y as f32;
+/// Gives a fieldless enum a name for each of its variants, `ALL` of its variants in order, and `Display` and
+/// `FromStr` which go by the name. Parsing ignores case and reads `_` as `-`, and `label` names the kind of value in
+/// the error.
+macro_rules! named_enum {
+    ($enum:ident, $label:literal, { $($variant:ident => $name:literal),+ $(,)? }) => {
+        impl $enum {
+            pub const ALL: [$enum; [$($name),+].len()] = [$($enum::$variant),+];
 
-    return f32::sqrt(x * x + y * y);
-}
+            pub fn get_name(&self) -> &str {
+                match self {
+                    $($enum::$variant => $name,)+
+                }
+            }
+        }
+
+        impl std::fmt::Display for $enum {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.get_name())
+            }
+        }
 
-pub fn is_valid_cell(map_size: &u32, x: i32, y: i32) -> bool {
-    if x < 0 || y < 0 {
-        return false;
-    }
+        impl std::str::FromStr for $enum {
+            type Err = String;
 
-    if x >= *map_size as i32 || y >= *map_size as i32 {
-        return false;
-    }
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                let value = value.to_ascii_lowercase().replace('_', "-");
+
+                $enum::ALL
+                    .into_iter()
+                    .find(|item| item.get_name() == value)
+                    .ok_or_else(|| {
+                        let names: Vec<&str> = $enum::ALL.iter().map(|item| item.get_name()).collect();
+                        format!("unknown {} '{}', expected one of: {}", $label, value, names.join(", "))
+                    })
+            }
+        }
+    };
+}
 
-    return true;
-}
\ No newline at end of file
+pub(crate) use named_enum;
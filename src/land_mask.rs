@@ -0,0 +1,66 @@
+use crate::error::GeneratorError;
+use crate::grid::Grid;
+use crate::steppers::map_position::MapPosition;
+use image::DynamicImage;
+use std::path::Path;
+
+// pixels at least this bright, and at least this opaque, are land
+const LAND_THRESHOLD: u8 = 128;
+
+/// The shape of the land, eg. sketched by hand, used instead of the landmass steppers.
+///
+/// The mask is stretched over the map, so it does not have to be the same size as the map.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LandMask {
+    land: Grid<bool>,
+}
+
+impl LandMask {
+    /// A mask from a grid in which land is `true`.
+    pub fn new(land: Grid<bool>) -> Result<Self, GeneratorError> {
+        if land.is_empty() {
+            return Err(GeneratorError::InvalidConfig("the land mask is empty".to_string()));
+        }
+
+        Ok(Self { land })
+    }
+
+    /// A mask from a greyscale or black and white image, bright pixels are land and dark or transparent pixels
+    /// are sea.
+    pub fn from_image(image: &DynamicImage) -> Result<Self, GeneratorError> {
+        let image = image.to_luma_alpha8();
+        let cells = image
+            .pixels()
+            .map(|pixel| pixel.0[0] >= LAND_THRESHOLD && pixel.0[1] >= LAND_THRESHOLD)
+            .collect();
+        let land = Grid::from_vec(image.width(), image.height(), cells)
+            .ok_or_else(|| GeneratorError::InvalidConfig("the land mask does not fill the image".to_string()))?;
+
+        Self::new(land)
+    }
+
+    /// Reads a mask from an image file, see [`LandMask::from_image`].
+    pub fn load(file_name: &Path) -> Result<Self, GeneratorError> {
+        Self::from_image(&image::open(file_name)?)
+    }
+
+    pub fn width(&self) -> u32 {
+        self.land.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.land.height()
+    }
+
+    /// Whether a position on a `width` by `height` map is land, the mask pixel under the middle of the tile
+    /// decides.
+    pub fn is_land(&self, position: MapPosition, width: u32, height: u32) -> bool {
+        let scale = |value: i32, size: u32, mask_size: u32| {
+            (((value as f64 + 0.5) * mask_size as f64 / size.max(1) as f64) as i32).clamp(0, mask_size as i32 - 1)
+        };
+        let x = scale(position.x, width, self.width());
+        let y = scale(position.y, height, self.height());
+
+        self.land[MapPosition { x, y }]
+    }
+}
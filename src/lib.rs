@@ -1,8 +1,25 @@
 use crate::biomes::Biome;
+use crate::grid::Grid;
 
 pub mod biomes;
+pub mod builder;
+pub mod chunk;
+pub mod constraints;
+pub mod distance;
+pub mod error;
+pub mod exporters;
 pub mod generator;
 pub mod steppers;
+pub mod grid;
 pub mod helper;
+pub mod land_mask;
+pub mod noise;
+pub mod output;
+pub mod preset;
+pub mod regions;
+pub mod serialization;
+pub mod shape;
+pub mod stats;
+pub mod topology;
 
-pub type MapData = Vec<Vec<Biome>>;
\ No newline at end of file
+pub type MapData = Grid<Biome>;
\ No newline at end of file
@@ -1,56 +1,330 @@
-use clap::Parser;
-use rand::distributions::Alphanumeric;
-use rand::Rng;
-use whittaker_map_generator::generator::Generator;
-use whittaker_map_generator::generator::Config::{
-    Debugging,
-    Steppers,
-    Steps,
-};
+use clap::{Parser, Subcommand};
+use std::path::Path;
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::biomes::table::BiomeTable;
+use whittaker_map_generator::constraints::Constraints;
+use whittaker_map_generator::error::GeneratorError;
+use whittaker_map_generator::generator::{Config, Generator};
+use whittaker_map_generator::land_mask::LandMask;
+use whittaker_map_generator::output::OutputFormat;
+use whittaker_map_generator::preset::Preset;
+use whittaker_map_generator::serialization::MapFormat;
+use whittaker_map_generator::shape::Shape;
+use whittaker_map_generator::topology::Topology;
 
 #[derive(Parser, Debug)]
 struct GeneratorArgs {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// the seed the map grows from, picked at random when left out
     #[arg(long, default_value_t = String::new())]
     seed: String,
 
+    /// width and height of a square map
     #[arg(long, default_value_t = 300)]
     size: u32,
 
+    /// width of the map, overrides --size
+    #[arg(long)]
+    width: Option<u32>,
+
+    /// height of the map, overrides --size
+    #[arg(long)]
+    height: Option<u32>,
+
     #[arg(long, default_value_t = 2)]
     rivers: u32,
 
+    #[arg(long, default_value_t = 0.5)]
+    equator: f32,
+
+    /// which edges of the map wrap around: bounded, cylindrical or toroidal
+    #[arg(long, default_value_t = Topology::Bounded)]
+    topology: Topology,
+
+    /// the shape of the tiles: square or hex
+    #[arg(long, default_value_t = Shape::Square)]
+    shape: Shape,
+
+    /// a json file with the biome table and the colour, symbol and name of the biomes
+    #[arg(long)]
+    biomes: Option<String>,
+
+    /// an image of the shape of the land, bright pixels are land and dark pixels are sea
+    #[arg(long)]
+    land_mask: Option<String>,
+
+    /// how many steppers roughen up the coast of the --land-mask
+    #[arg(long, default_value_t = 0)]
+    coast_steppers: u32,
+
+    /// how many steps each coast stepper takes
+    #[arg(long, default_value_t = 30)]
+    coast_steps: u32,
+
     #[arg(long, default_value_t = false)]
     debug: bool,
 
-    #[arg(long, default_value_t = 350)]
-    steppers: u32,
+    /// the shape of the world: archipelago, pangaea, continents, inland-sea or atoll-ring
+    #[arg(long)]
+    preset: Option<Preset>,
 
-    #[arg(long, default_value_t = 300)]
-    steps: u32,
+    /// how many landmass steppers there are, defaults to 350 or to the number the --preset uses
+    #[arg(long)]
+    steppers: Option<u32>,
+
+    /// how many steps each landmass stepper takes, defaults to 300 or to the number the --preset uses
+    #[arg(long)]
+    steps: Option<u32>,
+
+    /// the least share of the map which is land, from 0.0 to 1.0
+    #[arg(long)]
+    min_land: Option<f32>,
+
+    /// the largest share of the map which is land, from 0.0 to 1.0
+    #[arg(long)]
+    max_land: Option<f32>,
+
+    /// how many separate pieces of land the map has at least
+    #[arg(long)]
+    min_islands: Option<u32>,
+
+    /// how many tiles the largest piece of land has at least
+    #[arg(long)]
+    min_largest_island: Option<u32>,
+
+    /// a biome which has to grow somewhere on the map, by name, can be given multiple times
+    #[arg(long)]
+    require_biome: Vec<String>,
+
+    /// how many lakes the map has at least
+    #[arg(long)]
+    min_lakes: Option<u32>,
+
+    /// how many maps to generate at most while looking for one which meets the constraints
+    #[arg(long, default_value_t = 20)]
+    attempts: u32,
 
     #[arg(long, default_value_t = 4)]
     output_multiplier: u32,
 
-    #[arg(long, default_value_t = String::new())]
-    output_file: String,
+    /// pixel size of each tile in exported tilemaps
+    #[arg(long, default_value_t = 16)]
+    tile_size: u32,
+
+    /// where to write the map, can be given multiple times; defaults to output.png and output.txt
+    #[arg(long, alias = "output-file")]
+    output: Vec<String>,
+
+    /// the format of each --output, in the same order; inferred from the file extension when left out
+    #[arg(long)]
+    format: Vec<OutputFormat>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// print what the map is made up of, the map is only written out when --output is given
+    Stats {
+        /// a map written out as json or bin to read, instead of generating one
+        #[arg(long)]
+        map: Option<String>,
+
+        /// print the stats as json instead of text
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+}
+
+fn resolve_outputs(args: &GeneratorArgs) -> Result<Vec<(String, OutputFormat)>, String> {
+    if args.output.is_empty() {
+        if !args.format.is_empty() {
+            return Err("--format given without an --output to apply it to".to_string());
+        }
+
+        if args.command.is_some() {
+            return Ok(Vec::new());
+        }
+
+        return Ok(vec![
+            ("output.png".to_string(), OutputFormat::Png),
+            ("output.txt".to_string(), OutputFormat::Txt),
+        ]);
+    }
+
+    if args.format.len() > args.output.len() {
+        return Err("more --format values given than --output paths".to_string());
+    }
+
+    args.output
+        .iter()
+        .enumerate()
+        .map(|(index, file_name)| {
+            let format = match args.format.get(index) {
+                Some(format) => *format,
+                None => OutputFormat::from_path(Path::new(file_name))
+                    .ok_or_else(|| format!("cannot infer the output format of '{}', pass --format", file_name))?,
+            };
+
+            Ok((file_name.clone(), format))
+        })
+        .collect()
+}
+
+fn find_biome(table: &BiomeTable, name: &str) -> Result<Biomes, GeneratorError> {
+    let simplify = |name: &str| name.replace([' ', '-', '_'], "").to_ascii_lowercase();
+    Biomes::ALL
+        .into_iter()
+        .chain(table.custom_biomes().map(|(biome, _)| biome))
+        .find(|biome| simplify(table.get_name(*biome)) == simplify(name))
+        .ok_or_else(|| GeneratorError::InvalidConfig(format!("unknown biome '{}'", name)))
+}
+
+fn resolve_constraints(args: &GeneratorArgs, table: &BiomeTable) -> Result<Constraints, GeneratorError> {
+    let mut constraints = Constraints::new().max_attempts(args.attempts);
+
+    if args.min_land.is_some() || args.max_land.is_some() {
+        constraints = constraints.land_ratio(args.min_land.unwrap_or(0.0)..=args.max_land.unwrap_or(1.0));
+    }
+
+    if let Some(min_islands) = args.min_islands {
+        constraints = constraints.min_islands(min_islands);
+    }
+
+    if let Some(min_largest_island) = args.min_largest_island {
+        constraints = constraints.min_largest_island(min_largest_island);
+    }
+
+    if let Some(min_lakes) = args.min_lakes {
+        constraints = constraints.min_lakes(min_lakes);
+    }
+
+    for name in &args.require_biome {
+        constraints = constraints.require_biome(find_biome(table, name)?);
+    }
+
+    Ok(constraints)
+}
+
+fn load_map(file_name: &str, biome_table: Option<BiomeTable>) -> Result<Generator, GeneratorError> {
+    let format = match OutputFormat::from_path(Path::new(file_name)) {
+        Some(OutputFormat::Json) => MapFormat::Json,
+        Some(OutputFormat::Binary) => MapFormat::Binary,
+        _ => {
+            return Err(GeneratorError::InvalidConfig(format!(
+                "cannot read a map from '{}', expected a .json or .bin file",
+                file_name
+            )))
+        }
+    };
+    let mut generator = Generator::load(std::io::BufReader::new(std::fs::File::open(file_name)?), format)?;
+
+    // the map keeps the table it was generated with, --biomes only restyles it when it is given
+    if let Some(biome_table) = biome_table {
+        generator.set(Config::BiomeTable(biome_table));
+    }
+
+    Ok(generator)
+}
+
+fn generate_map(args: &GeneratorArgs, biome_table: BiomeTable) -> Result<Generator, GeneratorError> {
+    let land_mask = args.land_mask
+        .as_ref()
+        .map(|file_name| LandMask::load(Path::new(file_name)))
+        .transpose()?;
+    let constraints = resolve_constraints(args, &biome_table)?;
+    let mut builder = Generator::builder()
+        .seed(args.seed.clone())
+        .width(args.width.unwrap_or(args.size))
+        .height(args.height.unwrap_or(args.size));
+
+    if let Some(preset) = args.preset {
+        builder = builder.preset(preset);
+    }
+
+    if let Some(steppers) = args.steppers {
+        builder = builder.steppers(steppers);
+    }
+
+    if let Some(steps) = args.steps {
+        builder = builder.steps(steps);
+    }
+
+    let builder = builder
+        .rivers(args.rivers)
+        .equator(args.equator)
+        .topology(args.topology)
+        .shape(args.shape)
+        .biome_table(biome_table)
+        .coast_steppers(args.coast_steppers)
+        .coast_steps(args.coast_steps)
+        .debug(args.debug);
+
+    let mut generator = match land_mask {
+        Some(land_mask) => builder.land_mask(land_mask).build(),
+        None => builder.build(),
+    }?;
+
+    if constraints.is_empty() {
+        generator.generate()?;
+    } else {
+        eprintln!("{}", generator.generate_with_constraints(&constraints)?);
+    }
+
+    Ok(generator)
 }
 
 fn main() {
-    let mut args = GeneratorArgs::parse();
-
-    if args.seed.trim().is_empty() {
-        args.seed = rand::thread_rng()
-            .sample_iter(&Alphanumeric)
-            .take(32)
-            .map(char::from)
-            .collect();
-    }
-
-    Generator::new(args.seed, args.size)
-        .set(Debugging(args.debug))
-        .set(Steppers(args.steppers))
-        .set(Steps(args.steps))
-        .generate()
-        .output_image("output.png".to_string(), args.output_multiplier)
-        .output_file("output.txt".to_string());
+    let args = GeneratorArgs::parse();
+
+    let outputs = match resolve_outputs(&args) {
+        Ok(outputs) => outputs,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+
+    let biome_table = args.biomes
+        .as_ref()
+        .map(|file_name| BiomeTable::load(Path::new(file_name)))
+        .transpose();
+
+    let result = biome_table
+        .and_then(|biome_table| match &args.command {
+            Some(Command::Stats { map: Some(file_name), .. }) => load_map(file_name, biome_table),
+            _ => generate_map(&args, biome_table.unwrap_or_default()),
+        })
+        .and_then(|mut generator| {
+            for (file_name, format) in outputs {
+                let scale = match format {
+                    OutputFormat::Tmx => args.tile_size,
+                    _ => args.output_multiplier,
+                };
+
+                if let Some(parent) = Path::new(&file_name).parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+
+                generator.output(file_name, format, scale)?;
+            }
+
+            if let Some(Command::Stats { json, .. }) = &args.command {
+                let stats = generator.get_stats();
+
+                if *json {
+                    stats.to_writer(std::io::stdout().lock())?;
+                    println!();
+                } else {
+                    print!("{}", stats);
+                }
+            }
+
+            Ok(())
+        });
+
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
 }
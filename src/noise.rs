@@ -0,0 +1,131 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+const GRADIENTS: [(f64, f64); 8] = [
+    (1.0, 0.0),
+    (-1.0, 0.0),
+    (0.0, 1.0),
+    (0.0, -1.0),
+    (std::f64::consts::FRAC_1_SQRT_2, std::f64::consts::FRAC_1_SQRT_2),
+    (-std::f64::consts::FRAC_1_SQRT_2, std::f64::consts::FRAC_1_SQRT_2),
+    (std::f64::consts::FRAC_1_SQRT_2, -std::f64::consts::FRAC_1_SQRT_2),
+    (-std::f64::consts::FRAC_1_SQRT_2, -std::f64::consts::FRAC_1_SQRT_2),
+];
+
+/// Seeded 2D gradient (perlin) noise, layered into fractal noise for height fields.
+#[derive(Clone, Debug)]
+pub struct Noise {
+    permutation: Vec<usize>,
+}
+
+impl Noise {
+    /// Creates a new noise source, the permutation table is shuffled with the given rng so the same seed always
+    /// produces the same noise.
+    pub fn new<R: Rng>(rng: &mut R) -> Self {
+        let mut permutation: Vec<usize> = (0..256).collect();
+        permutation.shuffle(rng);
+        permutation.extend_from_within(..);
+
+        Self { permutation }
+    }
+
+    /// Samples the noise at the given point, returns a value in roughly the -1.0..=1.0 range.
+    pub fn get(&self, x: f64, y: f64) -> f64 {
+        self.get_periodic(x, y, None, None)
+    }
+
+    /// Samples noise which repeats itself every `x_period` and `y_period` units along the axes that have one, so
+    /// the edges of a wrapping map line up.
+    pub fn get_periodic(&self, x: f64, y: f64, x_period: Option<u32>, y_period: Option<u32>) -> f64 {
+        let x_floor = x.floor();
+        let y_floor = y.floor();
+        let (x_cell, x_next) = lattice_cells(x_floor as i64, x_period);
+        let (y_cell, y_next) = lattice_cells(y_floor as i64, y_period);
+        let x_rel = x - x_floor;
+        let y_rel = y - y_floor;
+
+        let top_left = self.gradient(x_cell, y_cell, x_rel, y_rel);
+        let top_right = self.gradient(x_next, y_cell, x_rel - 1.0, y_rel);
+        let bottom_left = self.gradient(x_cell, y_next, x_rel, y_rel - 1.0);
+        let bottom_right = self.gradient(x_next, y_next, x_rel - 1.0, y_rel - 1.0);
+
+        let x_fade = fade(x_rel);
+        let y_fade = fade(y_rel);
+
+        let top = lerp(top_left, top_right, x_fade);
+        let bottom = lerp(bottom_left, bottom_right, x_fade);
+
+        // the largest value a 2D perlin sample can reach is sqrt(0.5), scale it up to fill -1.0..=1.0
+        lerp(top, bottom, y_fade) * std::f64::consts::SQRT_2
+    }
+
+    /// Layers `octaves` samples of increasing frequency and decreasing amplitude on top of each other,
+    /// returns a value in roughly the -1.0..=1.0 range.
+    pub fn fractal(&self, x: f64, y: f64, octaves: u32) -> f64 {
+        self.fractal_periodic(x, y, octaves, None, None)
+    }
+
+    /// Same as [`Noise::fractal`], repeating every `x_period` and `y_period` units like [`Noise::get_periodic`].
+    pub fn fractal_periodic(&self, x: f64, y: f64, octaves: u32, x_period: Option<u32>, y_period: Option<u32>) -> f64 {
+        let mut total = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        let mut max_amplitude = 0.0;
+
+        for octave in 0..octaves {
+            // offset each octave so they do not all share the same lattice origin
+            let offset = octave as f64 * 31.7;
+
+            // each octave packs twice as many lattice cells into the same period
+            let scale = 1 << octave;
+
+            total += self.get_periodic(
+                x * frequency + offset,
+                y * frequency + offset,
+                x_period.map(|period| period * scale),
+                y_period.map(|period| period * scale),
+            ) * amplitude;
+            max_amplitude += amplitude;
+            frequency *= 2.0;
+            amplitude *= 0.5;
+        }
+
+        if max_amplitude == 0.0 {
+            return 0.0;
+        }
+
+        total / max_amplitude
+    }
+
+    fn gradient(&self, x_cell: usize, y_cell: usize, x_rel: f64, y_rel: f64) -> f64 {
+        let hash = self.permutation[self.permutation[x_cell] + y_cell];
+        let (x_gradient, y_gradient) = GRADIENTS[hash % GRADIENTS.len()];
+
+        x_gradient * x_rel + y_gradient * y_rel
+    }
+}
+
+// the lattice cell a coordinate falls in and the one after it, wrapped around the period when there is one
+fn lattice_cells(floor: i64, period: Option<u32>) -> (usize, usize) {
+    match period {
+        Some(period) => {
+            let period = period.max(1) as i64;
+            let cell = floor.rem_euclid(period);
+
+            ((cell & 255) as usize, (((cell + 1) % period) & 255) as usize)
+        }
+        None => {
+            let cell = (floor & 255) as usize;
+
+            (cell, cell + 1)
+        }
+    }
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(from: f64, to: f64, t: f64) -> f64 {
+    from + (to - from) * t
+}
@@ -0,0 +1,32 @@
+use crate::helper::named_enum;
+use std::path::Path;
+
+/// Every format a generated map can be written out as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// the rendered map as an image
+    Png,
+    /// the elevation, moisture and biome symbols as text
+    Txt,
+    /// the full map as json, can be loaded again
+    Json,
+    /// the full map in a compact binary encoding, can be loaded again
+    Binary,
+    /// a Tiled map with its tileset
+    Tmx,
+}
+
+named_enum!(OutputFormat, "output format", {
+    Png => "png",
+    Txt => "txt",
+    Json => "json",
+    Binary => "bin",
+    Tmx => "tmx",
+});
+
+impl OutputFormat {
+    /// Infers the format from the extension of a file name.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
@@ -0,0 +1,83 @@
+use crate::helper::named_enum;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+
+/// Where the landmass steppers start from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StartLayout {
+    /// a random number of starts anywhere within `spread` of the middle of the map, as a fraction of half the map
+    /// size
+    Centre { starts: RangeInclusive<u32>, spread: f32 },
+    /// a random number of starts anywhere on the map, away from the edges
+    Scattered { starts: RangeInclusive<u32> },
+    /// a random number of starts spaced evenly around an ellipse `radius` from the middle of the map, as a fraction
+    /// of half the map size
+    Ring { starts: RangeInclusive<u32>, radius: f32 },
+}
+
+/// A named shape of the world, which sets where the landmass steppers start, how many there are, how far they walk
+/// and how much land they paint around them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Preset {
+    /// lots of small islands scattered over the whole map
+    Archipelago,
+    /// one large supercontinent in the middle of the map
+    Pangaea,
+    /// a handful of separate continents with oceans between them
+    Continents,
+    /// a ring of land around a large body of water in the middle of the map
+    InlandSea,
+    /// a ring of small islands around an empty lagoon
+    AtollRing,
+}
+
+named_enum!(Preset, "preset", {
+    Archipelago => "archipelago",
+    Pangaea => "pangaea",
+    Continents => "continents",
+    InlandSea => "inland-sea",
+    AtollRing => "atoll-ring",
+});
+
+impl Preset {
+    pub fn start_layout(&self) -> StartLayout {
+        match self {
+            Preset::Archipelago => StartLayout::Scattered { starts: 30..=50 },
+            Preset::Pangaea => StartLayout::Centre { starts: 1..=3, spread: 0.15 },
+            Preset::Continents => StartLayout::Ring { starts: 3..=5, radius: 0.55 },
+            Preset::InlandSea => StartLayout::Ring { starts: 14..=18, radius: 0.6 },
+            Preset::AtollRing => StartLayout::Ring { starts: 20..=28, radius: 0.6 },
+        }
+    }
+
+    pub fn steppers(&self) -> u32 {
+        match self {
+            Preset::Archipelago => 150,
+            Preset::Pangaea => 450,
+            Preset::Continents => 300,
+            Preset::InlandSea => 500,
+            Preset::AtollRing => 150,
+        }
+    }
+
+    pub fn steps(&self) -> u32 {
+        match self {
+            Preset::Archipelago => 40,
+            Preset::Pangaea => 300,
+            Preset::Continents => 130,
+            Preset::InlandSea => 150,
+            Preset::AtollRing => 25,
+        }
+    }
+
+    /// How many tiles out from its path a landmass stepper paints land.
+    pub fn fill_size(&self) -> u32 {
+        match self {
+            Preset::Archipelago => 2,
+            Preset::Pangaea => 4,
+            Preset::Continents => 3,
+            Preset::InlandSea => 4,
+            Preset::AtollRing => 2,
+        }
+    }
+}
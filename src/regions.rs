@@ -0,0 +1,262 @@
+use crate::biomes::biomes::Biomes;
+use crate::biomes::Biome;
+use crate::grid::Grid;
+use crate::steppers::map_position::MapPosition;
+use crate::MapData;
+use std::collections::VecDeque;
+
+/// What a region of the map is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RegionKind {
+    /// a separate piece of land
+    Island,
+    /// fresh water which is more than a tile wide somewhere
+    Lake,
+    /// fresh water which is never more than a tile wide
+    River,
+    /// salt water, usually one ocean surrounding everything else
+    Ocean,
+    /// adjacent tiles of the same biome
+    BiomePatch(Biomes),
+}
+
+/// A group of adjacent tiles, see [`Regions`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Region {
+    pub id: usize,
+    pub kind: RegionKind,
+    /// the number of tiles in the region
+    pub area: usize,
+    /// the top left corner of the box around the region
+    pub min: MapPosition,
+    /// the bottom right corner of the box around the region, it runs past the east or south edge when the region
+    /// crosses an edge which wraps around
+    pub max: MapPosition,
+    /// the average position of the tiles in the region
+    pub centroid: (f32, f32),
+}
+
+impl Region {
+    /// The width and height of the box around the region.
+    pub fn size(&self) -> (u32, u32) {
+        ((self.max.x - self.min.x + 1) as u32, (self.max.y - self.min.y + 1) as u32)
+    }
+}
+
+/// Labels every tile of a map with the region it belongs to, so the islands, lakes or biome patches can be listed
+/// and looked up by position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regions {
+    ids: Grid<Option<usize>>,
+    regions: Vec<Region>,
+}
+
+// the regions of the map by landmass
+#[derive(Clone, Copy, PartialEq)]
+enum Landmass {
+    Land,
+    SaltWater,
+    FreshWater,
+}
+
+impl Regions {
+    /// Splits the map into islands, lakes, rivers and oceans. Tiles which have not been generated yet are not in
+    /// any region.
+    pub fn landmasses(map_data: &MapData) -> Self {
+        let landmass = |tile: &Biome| match tile.tile_type {
+            Biomes::Void => None,
+            Biomes::SaltWater => Some(Landmass::SaltWater),
+            Biomes::FreshWater => Some(Landmass::FreshWater),
+            _ => Some(Landmass::Land),
+        };
+
+        Self::label(map_data, landmass, |positions, landmass| match landmass {
+            Landmass::Land => RegionKind::Island,
+            Landmass::SaltWater => RegionKind::Ocean,
+            Landmass::FreshWater if is_lake(map_data, positions) => RegionKind::Lake,
+            Landmass::FreshWater => RegionKind::River,
+        })
+    }
+
+    /// Splits the map into patches of adjacent tiles of the same biome, water included.
+    pub fn biome_patches(map_data: &MapData) -> Self {
+        Self::label(map_data, |tile| Some(tile.tile_type), |_, biome| RegionKind::BiomePatch(biome))
+    }
+
+    /// Every region, in the order of their ids.
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    /// Every region of the given kind.
+    pub fn of_kind(&self, kind: RegionKind) -> impl Iterator<Item = &Region> {
+        self.regions.iter().filter(move |region| region.kind == kind)
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Region> {
+        self.regions.get(id)
+    }
+
+    /// The id of the region the position is in.
+    pub fn id_at(&self, position: MapPosition) -> Option<usize> {
+        self.ids.get(position).copied().flatten()
+    }
+
+    /// The region the position is in.
+    pub fn at(&self, position: MapPosition) -> Option<&Region> {
+        self.id_at(position).and_then(|id| self.get(id))
+    }
+
+    /// Every position in a region.
+    pub fn positions(&self, id: usize) -> impl Iterator<Item = MapPosition> + '_ {
+        self.ids
+            .enumerate()
+            .filter(move |(_, region)| **region == Some(id))
+            .map(|(position, _)| position)
+    }
+
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    // floods out from every tile which is not in a region yet over the adjacent tiles with the same key
+    fn label<K: Copy + PartialEq>(
+        map_data: &MapData,
+        key: impl Fn(&Biome) -> Option<K>,
+        kind: impl Fn(&[MapPosition], K) -> RegionKind,
+    ) -> Self {
+        let mut ids = map_data.map(|_| None);
+        let mut regions = Vec::new();
+
+        for start in map_data.positions() {
+            let Some(start_key) = key(&map_data[start]).filter(|_| ids[start].is_none()) else {
+                continue;
+            };
+
+            let id = regions.len();
+            let mut positions = Vec::new();
+            let mut queue = VecDeque::from([start]);
+
+            ids[start] = Some(id);
+
+            while let Some(position) = queue.pop_front() {
+                positions.push(position);
+
+                for neighbour in map_data.adjacent(position) {
+                    if ids[neighbour].is_none() && key(&map_data[neighbour]) == Some(start_key) {
+                        ids[neighbour] = Some(id);
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+
+            regions.push(Self::measure(map_data, id, kind(&positions, start_key), &positions));
+        }
+
+        Self { ids, regions }
+    }
+
+    fn measure(map_data: &MapData, id: usize, kind: RegionKind, positions: &[MapPosition]) -> Region {
+        let topology = map_data.topology();
+        let xs: Vec<i32> = positions.iter().map(|position| position.x).collect();
+        let ys: Vec<i32> = positions.iter().map(|position| position.y).collect();
+        let (min_x, max_x, centroid_x) = axis(&xs, map_data.width(), topology.wraps_x());
+        let (min_y, max_y, centroid_y) = axis(&ys, map_data.height(), topology.wraps_y());
+
+        Region {
+            id,
+            kind,
+            area: positions.len(),
+            min: MapPosition { x: min_x, y: min_y },
+            max: MapPosition { x: max_x, y: max_y },
+            centroid: (centroid_x, centroid_y),
+        }
+    }
+}
+
+/// Whether a region of fresh water is more than a tile wide anywhere, a river never is. It is wider where two tiles
+/// next to the same tile touch each other, as on hexes, or both touch another tile of it, as around a square pond.
+fn is_lake(map_data: &MapData, positions: &[MapPosition]) -> bool {
+    let is_fresh = |position: &MapPosition| map_data[*position].tile_type == Biomes::FreshWater;
+
+    positions.iter().any(|position| {
+        let neighbours: Vec<MapPosition> = map_data.adjacent(*position).filter(is_fresh).collect();
+
+        neighbours.iter().enumerate().any(|(index, first)| {
+            neighbours[index + 1..].iter().any(|second| {
+                map_data.adjacent(*first).any(|corner| {
+                    let square = corner != *position && is_fresh(&corner);
+
+                    corner == *second || (square && map_data.adjacent(*second).any(|other| other == corner))
+                })
+            })
+        })
+    })
+}
+
+// the first and last row or column a region covers and its average one. On an axis which wraps around the region
+// starts after the widest gap in it, so a region across the edge is not stretched over the whole map
+fn axis(values: &[i32], size: u32, wraps: bool) -> (i32, i32, f32) {
+    let mut occupied = vec![false; size as usize];
+
+    for value in values {
+        occupied[*value as usize] = true;
+    }
+
+    let first = occupied.iter().position(|occupied| *occupied).unwrap_or_default() as i32;
+    let last = occupied.iter().rposition(|occupied| *occupied).unwrap_or_default() as i32;
+    let (start, end) = if wraps {
+        widest_gap(&occupied)
+            .map(|(gap_start, gap_length)| {
+                let start = (gap_start + gap_length) % size as i32;
+
+                (start, start + size as i32 - gap_length - 1)
+            })
+            .unwrap_or((first, last))
+    } else {
+        (first, last)
+    };
+
+    // count the values before the start as past the far edge, then bring the average back onto the map
+    let unwrapped: f64 = values
+        .iter()
+        .map(|value| if *value < start { *value + size as i32 } else { *value } as f64)
+        .sum();
+    let centroid = (unwrapped / values.len().max(1) as f64) % size as f64;
+
+    (start, end, centroid as f32)
+}
+
+// the start and length of the longest run of unoccupied values, running across the end back to the start
+fn widest_gap(occupied: &[bool]) -> Option<(i32, i32)> {
+    let size = occupied.len();
+    let anchor = occupied.iter().position(|occupied| *occupied)?;
+    let mut widest: Option<(i32, i32)> = None;
+    let mut run_start = 0;
+    let mut run_length = 0;
+
+    // walk once around from an occupied value, so every gap is seen in one piece
+    for step in 1..=size {
+        let index = (anchor + step) % size;
+
+        if occupied[index] {
+            if run_length > widest.map(|(_, length)| length).unwrap_or_default() {
+                widest = Some((run_start as i32, run_length));
+            }
+
+            run_length = 0;
+        } else {
+            if run_length == 0 {
+                run_start = index;
+            }
+
+            run_length += 1;
+        }
+    }
+
+    widest
+}
@@ -0,0 +1,80 @@
+use crate::biomes::table::BiomeTable;
+use crate::error::GeneratorError;
+use crate::MapData;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapFormat {
+    /// human readable json
+    Json,
+    /// compact binary encoding
+    Binary,
+}
+
+/// A generated map together with everything needed to restore it exactly.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MapFile {
+    pub seed: String,
+    pub map_data: MapData,
+    /// the table the map was generated with, so custom biomes keep their names and colours
+    pub biome_table: BiomeTable,
+}
+
+// borrowed twin of MapFile, so a map can be written without cloning it first
+#[derive(Serialize)]
+struct MapFileRef<'a> {
+    seed: &'a str,
+    map_data: &'a MapData,
+    biome_table: &'a BiomeTable,
+}
+
+impl MapFile {
+    pub fn to_writer<W: Write>(&self, writer: W, format: MapFormat) -> Result<(), GeneratorError> {
+        write_map(writer, &self.seed, &self.map_data, &self.biome_table, format)
+    }
+
+    pub fn from_reader<R: Read>(reader: R, format: MapFormat) -> Result<Self, GeneratorError> {
+        // the grid checks its cells fill its width and height while it is deserialized
+        let map_file: MapFile = match format {
+            MapFormat::Json => serde_json::from_reader(reader)?,
+            MapFormat::Binary => bincode::deserialize_from(reader)?,
+        };
+
+        Ok(map_file)
+    }
+}
+
+/// Writes a map in the same layout as [`MapFile`].
+pub fn write_map<W: Write>(
+    writer: W,
+    seed: &str,
+    map_data: &MapData,
+    biome_table: &BiomeTable,
+    format: MapFormat,
+) -> Result<(), GeneratorError> {
+    let map_file = MapFileRef {
+        seed,
+        map_data,
+        biome_table,
+    };
+
+    match format {
+        MapFormat::Json => serde_json::to_writer(writer, &map_file)?,
+        MapFormat::Binary => bincode::serialize_into(writer, &map_file)?,
+    }
+
+    Ok(())
+}
+
+/// Reads just the map data from a map written with [`MapFile::to_writer`].
+pub fn read_map_data<R: Read>(reader: R, format: MapFormat) -> Result<MapData, GeneratorError> {
+    Ok(MapFile::from_reader(reader, format)?.map_data)
+}
+
+impl MapData {
+    /// Reads a map written with [`MapFile::to_writer`] back exactly, distances included, see [`read_map_data`].
+    pub fn from_reader<R: Read>(reader: R, format: MapFormat) -> Result<Self, GeneratorError> {
+        read_map_data(reader, format)
+    }
+}
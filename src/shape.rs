@@ -0,0 +1,104 @@
+use crate::helper::named_enum;
+use crate::steppers::direction::Direction;
+use crate::steppers::map_position::MapPosition;
+use crate::steppers::move_direction::MoveDirection;
+use serde::{Deserialize, Serialize};
+
+// the six axial directions of a hex, starting east and going round anti-clockwise
+const HEX_DIRECTIONS: [MoveDirection; 6] = [
+    MoveDirection { x: 1, y: 0 },
+    MoveDirection { x: 1, y: -1 },
+    MoveDirection { x: 0, y: -1 },
+    MoveDirection { x: -1, y: 0 },
+    MoveDirection { x: -1, y: 1 },
+    MoveDirection { x: 0, y: 1 },
+];
+
+/// The shape of the tiles, and so which tiles are next to each other.
+///
+/// Hex maps use "odd-r" offset coordinates: the hexes are pointy topped, `x` is the column and `y` the row, and
+/// every odd row is pushed half a hex to the right.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Shape {
+    /// square tiles, with 4 tiles along the sides and 8 when the corners are included
+    #[default]
+    Square,
+    /// hexagonal tiles, with 6 tiles around each one
+    Hex,
+}
+
+named_enum!(Shape, "shape", {
+    Square => "square",
+    Hex => "hex",
+});
+
+impl Shape {
+    /// The offsets from `position` to the tiles which share a side with it.
+    pub fn get_adjacent_directions(&self, position: MapPosition) -> Vec<MoveDirection> {
+        match self {
+            Shape::Square => Direction::get_standard_directions(),
+            Shape::Hex => HEX_DIRECTIONS
+                .iter()
+                .map(|direction| {
+                    let next = self.step(position, *direction);
+
+                    MoveDirection { x: next.x - position.x, y: next.y - position.y }
+                })
+                .collect(),
+        }
+    }
+
+    /// The offsets from `position` to every tile touching it, including the ones which only share a corner.
+    pub fn get_surrounding_directions(&self, position: MapPosition) -> Vec<MoveDirection> {
+        match self {
+            Shape::Square => Direction::get_extended_directions(),
+            // hexes never meet at just a corner
+            Shape::Hex => self.get_adjacent_directions(position),
+        }
+    }
+
+    /// The directions a stepper can walk in, see [`Shape::step`].
+    pub fn get_walk_directions(&self) -> Vec<MoveDirection> {
+        match self {
+            Shape::Square => Direction::get_standard_directions(),
+            Shape::Hex => HEX_DIRECTIONS.to_vec(),
+        }
+    }
+
+    /// Takes one step in one of the [`Shape::get_walk_directions`]. Hex directions are axial, so a walker keeps
+    /// heading the same way no matter which row it is on.
+    pub fn step(&self, position: MapPosition, direction: MoveDirection) -> MapPosition {
+        match self {
+            Shape::Square => MapPosition {
+                x: position.x + direction.x,
+                y: position.y + direction.y,
+            },
+            Shape::Hex => {
+                let (q, r) = offset_to_axial(position);
+
+                axial_to_offset(q + direction.x, r + direction.y)
+            }
+        }
+    }
+
+    /// Where the middle of the tile sits, in tile widths from the middle of the first tile.
+    pub fn centre(&self, position: MapPosition) -> (f64, f64) {
+        match self {
+            Shape::Square => (position.x as f64, position.y as f64),
+            Shape::Hex => (position.x as f64 + 0.5 * (position.y & 1) as f64, position.y as f64),
+        }
+    }
+}
+
+/// Converts odd-r offset coordinates to axial coordinates.
+pub fn offset_to_axial(position: MapPosition) -> (i32, i32) {
+    (position.x - (position.y - (position.y & 1)) / 2, position.y)
+}
+
+/// Converts axial coordinates to odd-r offset coordinates.
+pub fn axial_to_offset(q: i32, r: i32) -> MapPosition {
+    MapPosition {
+        x: q + (r - (r & 1)) / 2,
+        y: r,
+    }
+}
@@ -0,0 +1,159 @@
+use crate::biomes::biomes::Biomes;
+use crate::biomes::table::BiomeTable;
+use crate::error::GeneratorError;
+use crate::regions::{RegionKind, Regions};
+use crate::MapData;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+
+// how many buckets the heights of the land are split into
+const ELEVATION_BUCKETS: usize = 10;
+// the moisture bands go from the driest (1) to the wettest (6)
+const MOISTURE_BANDS: u32 = 6;
+
+/// How many tiles of a biome are on the map.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BiomeStats {
+    pub biome: Biomes,
+    pub name: String,
+    pub tiles: usize,
+    /// the share of all tiles on the map, from 0.0 to 100.0
+    pub percentage: f32,
+}
+
+/// What a generated map is made up of, see [`Generator::get_stats`](crate::generator::Generator::get_stats).
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MapStats {
+    pub seed: String,
+    pub width: u32,
+    pub height: u32,
+    /// the share of the map which is land, from 0.0 to 1.0
+    pub land_ratio: f32,
+    /// the share of the map which is salt or fresh water, from 0.0 to 1.0
+    pub water_ratio: f32,
+    /// every biome on the map, the most common first
+    pub biomes: Vec<BiomeStats>,
+    /// the number of tiles on each separate piece of land, the largest first
+    pub islands: Vec<usize>,
+    /// the number of tiles in each lake, the largest first
+    pub lakes: Vec<usize>,
+    /// the number of tile edges between land and the sea
+    pub coastline: usize,
+    /// the number of land tiles per tenth of the height, from sea level up to the highest peak
+    pub elevation: Vec<usize>,
+    /// the number of land tiles per moisture band, from the driest to the wettest
+    pub moisture: Vec<usize>,
+}
+
+impl MapStats {
+    /// Counts up the map, the biome table names the biomes.
+    pub fn new(seed: &str, map_data: &MapData, table: &BiomeTable) -> Self {
+        let tiles = map_data.len().max(1);
+        let mut counts: HashMap<Biomes, usize> = HashMap::new();
+        let mut elevation = vec![0; ELEVATION_BUCKETS];
+        let mut moisture = vec![0; MOISTURE_BANDS as usize];
+        let mut coastline = 0;
+
+        for (position, tile) in map_data.enumerate() {
+            *counts.entry(tile.tile_type).or_default() += 1;
+
+            if !tile.is_land() {
+                continue;
+            }
+
+            let bucket = (tile.height * ELEVATION_BUCKETS as f32) as usize;
+
+            elevation[bucket.min(ELEVATION_BUCKETS - 1)] += 1;
+            moisture[tile.moisture.clamp(1, MOISTURE_BANDS) as usize - 1] += 1;
+            coastline += map_data
+                .adjacent(position)
+                .filter(|neighbour| map_data[*neighbour].tile_type == Biomes::SaltWater)
+                .count();
+        }
+
+        let mut biomes: Vec<BiomeStats> = counts
+            .into_iter()
+            .map(|(biome, count)| BiomeStats {
+                biome,
+                name: table.get_name(biome).to_string(),
+                tiles: count,
+                percentage: count as f32 / tiles as f32 * 100.0,
+            })
+            .collect();
+
+        biomes.sort_by(|a, b| b.tiles.cmp(&a.tiles).then(a.biome.get_id().cmp(&b.biome.get_id())));
+
+        let regions = Regions::landmasses(map_data);
+        let sizes = |kind: RegionKind| {
+            let mut sizes: Vec<usize> = regions.of_kind(kind).map(|region| region.area).collect();
+
+            sizes.sort_unstable_by(|a, b| b.cmp(a));
+            sizes
+        };
+        let land_ratio = land_ratio(map_data);
+
+        Self {
+            seed: seed.to_string(),
+            width: map_data.width(),
+            height: map_data.height(),
+            land_ratio,
+            water_ratio: 1.0 - land_ratio,
+            biomes,
+            islands: sizes(RegionKind::Island),
+            lakes: sizes(RegionKind::Lake),
+            coastline,
+            elevation,
+            moisture,
+        }
+    }
+
+    /// Writes the stats as json.
+    pub fn to_writer<W: Write>(&self, writer: W) -> Result<(), GeneratorError> {
+        serde_json::to_writer_pretty(writer, self)?;
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for MapStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let largest = |sizes: &[usize]| sizes.first().copied().unwrap_or_default();
+
+        writeln!(f, "Seed: {}", self.seed)?;
+        writeln!(f, "Size: {}x{}", self.width, self.height)?;
+        writeln!(f, "Land: {:.1}%, water: {:.1}%", self.land_ratio * 100.0, self.water_ratio * 100.0)?;
+        writeln!(f, "Islands: {}, the largest has {} tiles", self.islands.len(), largest(&self.islands))?;
+        writeln!(f, "Lakes: {}, the largest has {} tiles", self.lakes.len(), largest(&self.lakes))?;
+        writeln!(f, "Coastline: {} tile edges", self.coastline)?;
+
+        writeln!(f, "\nBiomes:")?;
+
+        for biome in &self.biomes {
+            writeln!(f, "  {:<26}{:>8} {:>6.2}%", biome.name, biome.tiles, biome.percentage)?;
+        }
+
+        writeln!(f, "\nElevation:")?;
+
+        for (bucket, tiles) in self.elevation.iter().enumerate() {
+            let from = bucket as f32 / ELEVATION_BUCKETS as f32;
+
+            writeln!(f, "  {:.1}-{:.1}{:>10}", from, from + 1.0 / ELEVATION_BUCKETS as f32, tiles)?;
+        }
+
+        writeln!(f, "\nMoisture:")?;
+
+        for (band, tiles) in self.moisture.iter().enumerate() {
+            writeln!(f, "  {}{:>10}", band + 1, tiles)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn land_ratio(map_data: &MapData) -> f32 {
+    let land = map_data.iter().filter(|tile| tile.is_land()).count();
+
+    land as f32 / map_data.len().max(1) as f32
+}
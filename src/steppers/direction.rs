@@ -30,21 +30,20 @@ impl Direction {
     }
 
     pub fn get_standard_directions() -> Vec<MoveDirection> {
-        let mut dirs: Vec<MoveDirection> = Vec::with_capacity(4);
-        // North
-        dirs.push(MoveDirection { x: 0, y: -1 });
-        // South
-        dirs.push(MoveDirection { x: 0, y: 1 });
-        // East
-        dirs.push(MoveDirection { x: 1, y: 0 });
-        // West
-        dirs.push(MoveDirection { x: -1, y: 0 });
-
-        dirs
+        vec![
+            // North
+            MoveDirection { x: 0, y: -1 },
+            // South
+            MoveDirection { x: 0, y: 1 },
+            // East
+            MoveDirection { x: 1, y: 0 },
+            // West
+            MoveDirection { x: -1, y: 0 },
+        ]
     }
 
     pub fn get_extended_directions() -> Vec<MoveDirection> {
-        let mut dirs: Vec<MoveDirection> = Vec::from(Direction::get_standard_directions());
+        let mut dirs: Vec<MoveDirection> = Direction::get_standard_directions();
         // NorthWest
         dirs.push(MoveDirection { x: -1, y: -1 });
         // NorthEast
@@ -58,8 +57,8 @@ impl Direction {
     }
 }
 
-pub fn pick_random_direction(rng: &mut Pcg64, exlude_directions: Vec<MoveDirection>) -> MoveDirection {
-    let mut dirs = Direction::get_standard_directions();
-    dirs = dirs.into_iter().filter(|a| !exlude_directions.contains(a)).collect();
+pub fn pick_random_direction(rng: &mut Pcg64, directions: &[MoveDirection], exlude_directions: Vec<MoveDirection>) -> MoveDirection {
+    let mut dirs = directions.to_vec();
+    dirs.retain(|a| !exlude_directions.contains(a));
     dirs[rng.gen_range(0..dirs.len())]
 }
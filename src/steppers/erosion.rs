@@ -0,0 +1,55 @@
+use crate::MapData;
+use crate::biomes::biomes::Biomes;
+use crate::steppers::MapPosition;
+use crate::steppers::StepperBehaviour;
+
+/// Washes the land away along the path of the stepper, a stepper which starts on the coast carves bays and inlets
+/// which stay open to the sea.
+pub struct Erosion {
+    radius: u32,
+}
+
+impl Erosion {
+    /// `radius` is how far the erosion reaches out from the path of the stepper.
+    pub fn create(radius: u32) -> Self {
+        Self {
+            radius,
+        }
+    }
+}
+
+impl StepperBehaviour for Erosion {
+    fn on_step(
+        &self,
+        map_data: &mut MapData,
+        current_pos: MapPosition,
+        _steps_left: u32,
+        _last_direction_steps: u32,
+    ) -> bool {
+        if !map_data.contains(current_pos) {
+            return false;
+        }
+
+        let radius = self.radius as i32;
+
+        for x in (current_pos.x - radius)..=(current_pos.x + radius) {
+            for y in (current_pos.y - radius)..=(current_pos.y + radius) {
+                let x_offset = x - current_pos.x;
+                let y_offset = y - current_pos.y;
+
+                // keep the bays round
+                if x_offset * x_offset + y_offset * y_offset > radius * radius {
+                    continue;
+                }
+
+                if let Some(tile) = map_data.get_mut(MapPosition { x, y }) {
+                    tile.tile_type = Biomes::Void;
+                }
+            }
+        }
+
+        true
+    }
+
+    fn on_last_step(&self, _map_data: &mut MapData, _current_pos: MapPosition) {}
+}
@@ -0,0 +1,76 @@
+use crate::MapData;
+use crate::biomes::biomes::Biomes;
+use crate::steppers::MapPosition;
+use crate::steppers::StepperBehaviour;
+
+// how many tiles of land are left between a lake and anything which is not land, the clean up of the coast after
+// the steppers only wears away land which is narrower than this
+const SHORE_WIDTH: i32 = 3;
+
+/// Digs a chain of fresh water lakes along the path of the stepper, lakes are only dug where they are surrounded
+/// by a wide enough shore of land that they never break through to the sea.
+pub struct Lake {
+    radius: u32,
+}
+
+impl Lake {
+    /// `radius` is how far each lake reaches out from the path of the stepper.
+    pub fn create(radius: u32) -> Self {
+        Self {
+            radius,
+        }
+    }
+
+    fn is_inland(&self, map_data: &MapData, current_pos: MapPosition) -> bool {
+        let radius = self.radius as i32 + SHORE_WIDTH;
+
+        for x in (current_pos.x - radius)..=(current_pos.x + radius) {
+            for y in (current_pos.y - radius)..=(current_pos.y + radius) {
+                match map_data.get(MapPosition { x, y }).map(|tile| tile.tile_type) {
+                    Some(Biomes::Placeholder) | Some(Biomes::FreshWater) => continue,
+                    _ => return false,
+                }
+            }
+        }
+
+        true
+    }
+}
+
+impl StepperBehaviour for Lake {
+    fn on_step(
+        &self,
+        map_data: &mut MapData,
+        current_pos: MapPosition,
+        _steps_left: u32,
+        _last_direction_steps: u32,
+    ) -> bool {
+        if !map_data.contains(current_pos) {
+            return false;
+        }
+
+        if !self.is_inland(map_data, current_pos) {
+            return true;
+        }
+
+        let radius = self.radius as i32;
+
+        for x in (current_pos.x - radius)..=(current_pos.x + radius) {
+            for y in (current_pos.y - radius)..=(current_pos.y + radius) {
+                let x_offset = x - current_pos.x;
+                let y_offset = y - current_pos.y;
+
+                // keep the lakes round
+                if x_offset * x_offset + y_offset * y_offset > radius * radius {
+                    continue;
+                }
+
+                map_data[MapPosition { x, y }].tile_type = Biomes::FreshWater;
+            }
+        }
+
+        true
+    }
+
+    fn on_last_step(&self, _map_data: &mut MapData, _current_pos: MapPosition) {}
+}
@@ -1,41 +1,28 @@
-use crate::helper::is_valid_cell;
 use crate::MapData;
 use crate::biomes::biomes::Biomes;
 use crate::steppers::MapPosition;
+use crate::steppers::StepperBehaviour;
 
-pub struct Landmass {}
+// how many tiles out from its path the stepper paints land, unless told otherwise
+const DEFAULT_FILL_SIZE: u32 = 4;
+
+/// Paints land along the path of the stepper.
+pub struct Landmass {
+    fill_size: u32,
+}
 
 impl Landmass {
     pub fn create() -> Self {
-        Self {}
-    }
-
-    pub fn on_step(
-        &self,
-        map_data: &mut MapData,
-        current_pos: MapPosition,
-        steps_left: u32,
-        last_direction_steps: u32,
-    ) -> bool {
-        if !is_valid_cell(&(map_data.len() as u32), current_pos.x, current_pos.y) {
-            return false;
-        }
-
-        if steps_left % 10 == 0 || last_direction_steps == 4 {
-            self.fill_area(map_data, current_pos, 4);
-        }
-
-        map_data[current_pos.x_usize()][current_pos.y_usize()].tile_type = Biomes::Placeholder;
-
-        true
+        Self::with_fill_size(DEFAULT_FILL_SIZE)
     }
 
-    pub fn on_last_step(&self, map_data: &mut MapData, current_pos: MapPosition) {
-        self.fill_area(map_data, current_pos, 4);
+    /// Paints land up to `fill_size` tiles out from the path of the stepper.
+    pub fn with_fill_size(fill_size: u32) -> Self {
+        Self { fill_size }
     }
 
     fn fill_area(&self, map_data: &mut MapData, current_pos: MapPosition, fill_size: u32) {
-        let fill_neg = fill_size as i32 * -1;
+        let fill_neg = -(fill_size as i32);
         let fill_pos = fill_size as i32;
         let mut x_offset = fill_neg;
         let mut y_offset = fill_neg;
@@ -43,23 +30,19 @@ impl Landmass {
         let max_y_offset = fill_pos;
 
         while x_offset <= max_x_offset {
-            let new_x = current_pos.y + x_offset;
+            let new_x = current_pos.x + x_offset;
 
             while y_offset <= max_y_offset {
-                let new_y = current_pos.x + y_offset;
+                let new_y = current_pos.y + y_offset;
 
                 // do not touch the far corners, to round it off a bit
-                if (x_offset == fill_neg && y_offset == fill_neg)
-                    && (x_offset == fill_pos && y_offset == fill_pos)
-                    && (x_offset == fill_neg && y_offset == fill_pos)
-                    && (x_offset == fill_pos && y_offset == fill_neg)
-                {
+                if x_offset.abs() == fill_pos && y_offset.abs() == fill_pos {
                     y_offset += 1;
                     continue;
                 }
 
-                if is_valid_cell(&(map_data.len() as u32), new_x, new_y) {
-                    map_data[new_x as usize][new_y as usize].tile_type = Biomes::Placeholder;
+                if let Some(tile) = map_data.get_mut(MapPosition { x: new_x, y: new_y }) {
+                    tile.tile_type = Biomes::Placeholder;
                 }
 
                 y_offset += 1;
@@ -70,3 +53,29 @@ impl Landmass {
         }
     }
 }
+
+impl StepperBehaviour for Landmass {
+    fn on_step(
+        &self,
+        map_data: &mut MapData,
+        current_pos: MapPosition,
+        steps_left: u32,
+        last_direction_steps: u32,
+    ) -> bool {
+        if !map_data.contains(current_pos) {
+            return false;
+        }
+
+        if steps_left.is_multiple_of(10) || last_direction_steps == 4 {
+            self.fill_area(map_data, current_pos, self.fill_size);
+        }
+
+        map_data[current_pos].tile_type = Biomes::Placeholder;
+
+        true
+    }
+
+    fn on_last_step(&self, map_data: &mut MapData, current_pos: MapPosition) {
+        self.fill_area(map_data, current_pos, self.fill_size);
+    }
+}
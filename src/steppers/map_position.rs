@@ -1,15 +1,9 @@
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct MapPosition {
     pub x: i32,
     pub y: i32,
 }
 
-impl PartialEq for MapPosition {
-    fn eq(&self, other: &Self) -> bool {
-        return format!("{}_{}", self.x, self.y) == format!("{}_{}", other.x, other.y);
-    }
-}
-
 impl MapPosition {
     pub fn x_usize(&self) -> usize {
         self.x as usize
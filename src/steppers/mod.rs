@@ -2,79 +2,97 @@ use rand::Rng;
 use crate::MapData;
 use crate::steppers::direction::pick_random_direction;
 use crate::steppers::map_position::MapPosition;
+use crate::shape::Shape;
+use crate::topology::Topology;
 use rand_pcg::Pcg64;
 
-use self::landmass::Landmass;
 use self::move_direction::MoveDirection;
 
 pub mod landmass;
+pub mod erosion;
+pub mod lake;
+pub mod ridge;
 pub mod direction;
 pub mod map_position;
 pub mod move_direction;
 
-#[derive(Clone, Copy)]
-pub enum Generators {
-    LandGenerator,
-}
-
-impl Generators {
-    pub fn get_generator(&self) -> Landmass{
-        match self {
-            Generators::LandGenerator => Landmass::create(),
-        }
-    }
+/// What a stepper does to the map as it walks across it.
+///
+/// A generator owns its behaviours and may be moved to another thread, so they have to be `Send + Sync`.
+pub trait StepperBehaviour: Send + Sync {
+    /// Called for every step the stepper takes, returning false stops the stepper early.
+    fn on_step(
+        &self,
+        map_data: &mut MapData,
+        current_pos: MapPosition,
+        steps_left: u32,
+        last_direction_steps: u32,
+    ) -> bool;
+
+    /// Called once the stepper has taken its last step.
+    fn on_last_step(&self, map_data: &mut MapData, current_pos: MapPosition);
 }
 
 pub struct Stepper {
     steps: u32,
-    map_size: i32,
+    width: i32,
+    height: i32,
     rng: Pcg64,
     start_pos: MapPosition,
 }
 
 impl Stepper {
-    pub fn create(rng: Pcg64, map_size: u32, steps: u32, start_pos: MapPosition) -> Self {
+    pub fn create(rng: Pcg64, width: u32, height: u32, steps: u32, start_pos: MapPosition) -> Self {
         Self {
-            map_size: map_size as i32,
+            width: width as i32,
+            height: height as i32,
             steps,
             rng,
             start_pos,
         }
     }
 
-    fn calculate_direction_exclusions(&mut self, current_pos: &MapPosition) -> Vec<MoveDirection> {
+    fn calculate_direction_exclusions(
+        &mut self,
+        current_pos: &MapPosition,
+        topology: Topology,
+        shape: Shape,
+        directions: &[MoveDirection],
+    ) -> Vec<MoveDirection> {
         let mut excludes: Vec<MoveDirection> = Vec::new();
-        let faction_map_sixe = self.map_size / 10;
+        let faction_width = self.width / 10;
+        let faction_height = self.height / 10;
 
-        if current_pos.x < faction_map_sixe {
-            excludes.push(MoveDirection { x: -1, y: 0 });
-        }
+        // steer away from the edges which do not wrap
+        for direction in directions {
+            let next = shape.step(*current_pos, *direction);
+            let (x, y) = (next.x - current_pos.x, next.y - current_pos.y);
 
-        if current_pos.y < faction_map_sixe {
-            excludes.push(MoveDirection { x: 0, y: -1 });
-        }
+            let towards_x = !topology.wraps_x()
+                && ((x < 0 && current_pos.x < faction_width) || (x > 0 && current_pos.x > self.width - faction_width));
+            let towards_y = !topology.wraps_y()
+                && ((y < 0 && current_pos.y < faction_height) || (y > 0 && current_pos.y > self.height - faction_height));
 
-        if current_pos.x > self.map_size - faction_map_sixe {
-            excludes.push(MoveDirection { x: 1, y: 0 });
-        }
-
-        if current_pos.y > self.map_size - faction_map_sixe {
-            excludes.push(MoveDirection { x: 0, y: 1 });
+            if towards_x || towards_y {
+                excludes.push(*direction);
+            }
         }
 
         excludes
     }
 
-    pub fn run(&mut self, map_data: &mut MapData, generator: Generators) {
+    pub fn run(&mut self, map_data: &mut MapData, behaviour: &dyn StepperBehaviour) {
+        let (topology, shape) = (map_data.topology(), map_data.shape());
+        let directions = shape.get_walk_directions();
         let mut excludes: Vec<MoveDirection> = Vec::new();
         let mut steps_left = self.steps;
         let mut last_direction: MoveDirection = pick_random_direction(
             &mut self.rng,
+            &directions,
             Vec::new(),
         );
         let mut current_direction: MoveDirection = last_direction;
         let mut last_direction_steps: u32 = 0;
-        let stepper_generator = generator.get_generator();
 
         // randomised a map position offset
         let mut current_pos = self.start_pos;
@@ -85,22 +103,26 @@ impl Stepper {
 
                 current_direction = pick_random_direction(
                     &mut self.rng,
+                    &directions,
                     excludes,
                 );
             }
 
-            current_pos.x = current_pos.x + current_direction.x;
-            current_pos.y = current_pos.y + current_direction.y;
+            let mut next_pos = shape.step(current_pos, current_direction);
 
-            if current_pos.x <= 0 || current_pos.x >= self.map_size - 1 {
-                current_pos.x = current_pos.x + (current_direction.x * 2) * -1;
-            }
+            // bounce off the edges which do not wrap, and come back in on the other side of the ones which do
+            let bounce_x = !topology.wraps_x() && next_pos.x != current_pos.x
+                && (next_pos.x <= 0 || next_pos.x >= self.width - 1);
+            let bounce_y = !topology.wraps_y() && next_pos.y != current_pos.y
+                && (next_pos.y <= 0 || next_pos.y >= self.height - 1);
 
-            if current_pos.y <= 0 || current_pos.y >= self.map_size - 1 {
-                current_pos.y += (current_direction.y * 2) * -1;
+            if bounce_x || bounce_y {
+                next_pos = shape.step(current_pos, current_direction.opposite_direction());
             }
 
-            excludes = self.calculate_direction_exclusions(&current_pos);
+            current_pos = map_data.wrap(next_pos).unwrap_or(next_pos);
+
+            excludes = self.calculate_direction_exclusions(&current_pos, topology, shape, &directions);
 
             if last_direction != current_direction {
                 last_direction_steps = 0;
@@ -109,12 +131,12 @@ impl Stepper {
 
             last_direction_steps += 1;
 
-            let proceed = stepper_generator.on_step(map_data, current_pos, steps_left, last_direction_steps);
+            let proceed = behaviour.on_step(map_data, current_pos, steps_left, last_direction_steps);
 
             steps_left -= 1;
 
             if steps_left == 0 {
-                stepper_generator.on_last_step(map_data, current_pos);
+                behaviour.on_last_step(map_data, current_pos);
             }
 
             if !proceed {
@@ -1,4 +1,4 @@
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct MoveDirection {
     pub x: i32,
     pub y: i32,
@@ -7,14 +7,8 @@ pub struct MoveDirection {
 impl MoveDirection {
     pub fn opposite_direction(&self) -> MoveDirection {
         MoveDirection {
-            x: self.x * -1,
-            y: self.y * -1,
+            x: -self.x,
+            y: -self.y,
         }
     }
 }
-
-impl PartialEq for MoveDirection {
-    fn eq(&self, other: &Self) -> bool {
-        return format!("{}_{}", self.x, self.y) == format!("{}_{}", other.x, other.y);
-    }
-}
\ No newline at end of file
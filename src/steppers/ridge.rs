@@ -0,0 +1,57 @@
+use crate::MapData;
+use crate::biomes::biomes::Biomes;
+use crate::steppers::MapPosition;
+use crate::steppers::StepperBehaviour;
+
+/// Raises a mountain ridge along the path of the stepper, the ridge only rises from land.
+pub struct Ridge {
+    height: f32,
+    radius: u32,
+}
+
+impl Ridge {
+    /// `height` is how far the crest of the ridge is raised, `radius` how far its slopes reach out from the crest.
+    pub fn create(height: f32, radius: u32) -> Self {
+        Self {
+            height,
+            radius: radius.max(1),
+        }
+    }
+}
+
+impl StepperBehaviour for Ridge {
+    fn on_step(
+        &self,
+        map_data: &mut MapData,
+        current_pos: MapPosition,
+        _steps_left: u32,
+        _last_direction_steps: u32,
+    ) -> bool {
+        if !map_data.contains(current_pos) {
+            return false;
+        }
+
+        let radius = self.radius as i32;
+
+        for x in (current_pos.x - radius)..=(current_pos.x + radius) {
+            for y in (current_pos.y - radius)..=(current_pos.y + radius) {
+                let Some(tile) = map_data.get_mut(MapPosition { x, y }) else {
+                    continue;
+                };
+
+                if let Biomes::Placeholder = tile.tile_type {
+                    // the offset, not the wrapped position, so tiles across the seam are as close as they look
+                    let distance = ((x - current_pos.x) as f32).hypot((y - current_pos.y) as f32);
+                    let slope = 1.0 - distance / self.radius as f32;
+
+                    // overlapping steps do not stack, the ridge keeps the height of its highest crest
+                    tile.height = tile.height.max(self.height * slope);
+                }
+            }
+        }
+
+        true
+    }
+
+    fn on_last_step(&self, _map_data: &mut MapData, _current_pos: MapPosition) {}
+}
@@ -0,0 +1,32 @@
+use crate::helper::named_enum;
+use serde::{Deserialize, Serialize};
+
+/// How the edges of the map join up.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Topology {
+    /// the map ends at every edge and is surrounded by sea
+    #[default]
+    Bounded,
+    /// the east and west edges join up, like the map of a globe
+    Cylindrical,
+    /// the east and west edges join up, and so do the north and south edges
+    Toroidal,
+}
+
+named_enum!(Topology, "topology", {
+    Bounded => "bounded",
+    Cylindrical => "cylindrical",
+    Toroidal => "toroidal",
+});
+
+impl Topology {
+    /// Whether walking off the east edge comes back in on the west edge.
+    pub fn wraps_x(&self) -> bool {
+        matches!(self, Topology::Cylindrical | Topology::Toroidal)
+    }
+
+    /// Whether walking off the south edge comes back in on the north edge.
+    pub fn wraps_y(&self) -> bool {
+        matches!(self, Topology::Toroidal)
+    }
+}
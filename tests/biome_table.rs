@@ -0,0 +1,99 @@
+use whittaker_map_generator::biomes::biomes::{Biomes, WHITTAKER};
+use whittaker_map_generator::biomes::table::BiomeTable;
+use whittaker_map_generator::generator::Generator;
+
+const CUSTOM: &str = r#"{
+    "temperature_bands": 2,
+    "moisture_bands": 3,
+    "table": [
+        ["SubtropicalDesert", "Grassland", "TropicalRainForest"],
+        ["Tundra", "Taiga", "Snow"]
+    ],
+    "biomes": [
+        { "biome": "Snow", "name": "Glacier", "symbol": "*", "colour": [230, 240, 255] }
+    ]
+}"#;
+
+#[test]
+fn default_table_is_the_whittaker_diagram() {
+    let table = BiomeTable::default();
+
+    for (row, biomes) in WHITTAKER.iter().enumerate() {
+        // the middle of each temperature band, from the hottest down
+        let temperature = 1.0 - (row as f32 + 0.5) / WHITTAKER.len() as f32;
+
+        for (column, biome) in biomes.iter().enumerate() {
+            assert_eq!(table.get_biome(temperature, column as u32 + 1), *biome);
+        }
+    }
+
+    for biome in Biomes::ALL {
+        assert_eq!(table.get_colour(biome), biome.get_colour());
+        assert_eq!(table.get_symbol(biome), biome.get_symbol());
+        assert_eq!(table.get_name(biome), biome.get_name());
+    }
+}
+
+#[test]
+fn default_table_round_trips_through_json() {
+    let mut json = Vec::new();
+    BiomeTable::default().to_writer(&mut json).expect("the table serializes");
+
+    assert_eq!(BiomeTable::from_reader(json.as_slice()).expect("the table parses"), BiomeTable::default());
+}
+
+#[test]
+fn custom_table_retunes_the_map() {
+    let table = BiomeTable::from_reader(CUSTOM.as_bytes()).expect("the table parses");
+
+    assert_eq!(table.get_biome(1.0, 1), Biomes::SubtropicalDesert);
+    assert_eq!(table.get_biome(1.0, 6), Biomes::TropicalRainForest);
+    assert_eq!(table.get_biome(0.0, 3), Biomes::Taiga);
+    assert_eq!(table.get_name(Biomes::Snow), "Glacier");
+    assert_eq!(table.get_symbol(Biomes::Snow), "*");
+    // biomes left out of the file keep their built-in style
+    assert_eq!(table.get_colour(Biomes::Taiga), Biomes::Taiga.get_colour());
+
+    let mut generator = Generator::builder()
+        .seed("biome table")
+        .map_size(120)
+        .biome_table(table)
+        .build()
+        .expect("the config is valid");
+
+    generator.generate().expect("the map generates");
+
+    let allowed = [
+        Biomes::SubtropicalDesert,
+        Biomes::Grassland,
+        Biomes::TropicalRainForest,
+        Biomes::Tundra,
+        Biomes::Taiga,
+        Biomes::Snow,
+    ];
+
+    for tile in generator.get_map_data().iter().filter(|tile| tile.tile_type.is_whittaker()) {
+        assert!(allowed.contains(&tile.tile_type), "{:?} is not in the table", tile.tile_type);
+    }
+}
+
+#[test]
+fn malformed_tables_are_rejected() {
+    let tables = [
+        r#"{ "temperature_bands": 0, "moisture_bands": 1, "table": [] }"#,
+        r#"{ "temperature_bands": 2, "moisture_bands": 1, "table": [["Snow"]] }"#,
+        r#"{ "temperature_bands": 1, "moisture_bands": 2, "table": [["Snow"]] }"#,
+        r#"{ "temperature_bands": 1, "moisture_bands": 1, "table": [["Lava"]] }"#,
+        r#"{
+            "temperature_bands": 1, "moisture_bands": 1, "table": [["Snow"]],
+            "biomes": [
+                { "biome": "Snow", "name": "a", "symbol": "a", "colour": [0, 0, 0] },
+                { "biome": "Snow", "name": "b", "symbol": "b", "colour": [0, 0, 0] }
+            ]
+        }"#,
+    ];
+
+    for table in tables {
+        assert!(BiomeTable::from_reader(table.as_bytes()).is_err(), "{} parsed", table);
+    }
+}
@@ -0,0 +1,13 @@
+use whittaker_map_generator::generator::Generator;
+
+#[test]
+fn a_random_seed_is_picked_when_none_is_given() {
+    let generator = Generator::builder().build().expect("the defaults are valid");
+    let blank = Generator::builder().seed("  ").build().expect("a blank seed is replaced");
+
+    assert_eq!(generator.get_seed().len(), 32);
+    assert!(generator.get_seed().chars().all(|letter| letter.is_ascii_alphanumeric()));
+    assert!(!blank.get_seed().trim().is_empty());
+    assert_ne!(generator.get_seed(), blank.get_seed());
+    assert_eq!(Generator::builder().seed("given").build().expect("the seed is valid").get_seed(), "given");
+}
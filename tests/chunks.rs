@@ -0,0 +1,136 @@
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::biomes::table::{BiomeDefinition, BiomeTable, PlacementRule};
+use whittaker_map_generator::chunk::{generate_chunk, generate_chunk_with_table, Chunk};
+use whittaker_map_generator::steppers::map_position::MapPosition;
+
+const SEED: &str = "endless";
+
+fn assert_same_tile(chunk: &Chunk, position: MapPosition, other: &Chunk, other_position: MapPosition) {
+    let tile = &chunk.map_data[position];
+    let other_tile = &other.map_data[other_position];
+
+    assert_eq!(chunk.world_position(position), other.world_position(other_position));
+    assert!(
+        tile.tile_type == other_tile.tile_type
+            && tile.height == other_tile.height
+            && tile.temperature == other_tile.temperature
+            && tile.moisture == other_tile.moisture,
+        "the tile at {:?} differs between chunk ({}, {}) and chunk ({}, {})",
+        chunk.world_position(position),
+        chunk.chunk_x,
+        chunk.chunk_y,
+        other.chunk_x,
+        other.chunk_y
+    );
+}
+
+#[test]
+fn stitched_chunks_match_one_large_chunk() {
+    // a 2x2 block of small chunks covers the same part of the world as one chunk twice the size, including across
+    // the world origin
+    for (large_x, large_y) in [(0, 0), (-1, -1), (3, -2)] {
+        let large = generate_chunk(SEED, large_x, large_y, 64).expect("the chunk generates");
+
+        for (offset_x, offset_y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let small = generate_chunk(SEED, large_x * 2 + offset_x, large_y * 2 + offset_y, 32)
+                .expect("the chunk generates");
+
+            for position in small.map_data.positions() {
+                let large_position = MapPosition { x: position.x + offset_x * 32, y: position.y + offset_y * 32 };
+
+                assert_same_tile(&small, position, &large, large_position);
+            }
+        }
+    }
+}
+
+#[test]
+fn neighbouring_chunks_continue_across_their_edges() {
+    let chunk = generate_chunk(SEED, 5, 7, 48).expect("the chunk generates");
+    let east = generate_chunk(SEED, 6, 7, 48).expect("the chunk generates");
+    let south = generate_chunk(SEED, 5, 8, 48).expect("the chunk generates");
+    // generated on its own, straddling the edges of all three chunks
+    let straddling = generate_chunk(SEED, 7, 9, 40).expect("the chunk generates");
+
+    for i in 0..48 {
+        let (east_edge, east_start) = (MapPosition { x: 47, y: i }, MapPosition { x: 0, y: i });
+        let (south_edge, south_start) = (MapPosition { x: i, y: 47 }, MapPosition { x: i, y: 0 });
+
+        assert_eq!(chunk.world_position(east_edge).0 + 1, east.world_position(east_start).0);
+        assert_eq!(chunk.world_position(south_edge).1 + 1, south.world_position(south_start).1);
+    }
+
+    let mut compared = [0; 3];
+
+    for position in straddling.map_data.positions() {
+        let (x, y) = straddling.world_position(position);
+        let (origin_x, origin_y) = chunk.origin();
+        let (local_x, local_y) = ((x - origin_x) as i32, (y - origin_y) as i32);
+
+        let (index, other, other_position) = match (local_x >= 48, local_y >= 48) {
+            (false, false) => (0, &chunk, MapPosition { x: local_x, y: local_y }),
+            (true, false) => (1, &east, MapPosition { x: local_x - 48, y: local_y }),
+            (false, true) => (2, &south, MapPosition { x: local_x, y: local_y - 48 }),
+            (true, true) => continue,
+        };
+
+        assert_same_tile(&straddling, position, other, other_position);
+        compared[index] += 1;
+    }
+
+    assert!(compared.iter().all(|count| *count > 0), "the chunk straddles all three chunks");
+}
+
+#[test]
+fn chunks_are_deterministic_and_fully_generated() {
+    let chunk = generate_chunk(SEED, -4, -4, 64).expect("the chunk generates");
+    let again = generate_chunk(SEED, -4, -4, 64).expect("the chunk generates");
+    let other_seed = generate_chunk("another world", -4, -4, 64).expect("the chunk generates");
+
+    for position in chunk.map_data.positions() {
+        assert_same_tile(&chunk, position, &again, position);
+        assert!(!matches!(chunk.map_data[position].tile_type, Biomes::Void | Biomes::Placeholder));
+    }
+
+    assert!(chunk.map_data.iter().any(|tile| tile.tile_type != Biomes::SaltWater), "the chunk has land on it");
+    assert!(chunk.map_data.iter().zip(other_seed.map_data.iter()).any(|(tile, other)| tile.tile_type != other.tile_type));
+}
+
+#[test]
+fn chunks_are_placed_by_the_biome_table() {
+    let mut table = BiomeTable::default();
+    let highlands = table.register(BiomeDefinition {
+        name: "Highlands".to_string(),
+        symbol: "h".to_string(),
+        colour: [120, 100, 80],
+        rule: Some(PlacementRule { height: Some([0.5, 1.0]), ..PlacementRule::default() }),
+    }).expect("the biome is registered");
+
+    for temperature_band in 1..=4 {
+        for moisture_band in 1..=6 {
+            table.set_biome(temperature_band, moisture_band, Biomes::Grassland).expect("the cell is in the table");
+        }
+    }
+
+    let chunk = generate_chunk_with_table(SEED, -4, -4, 64, &table).expect("the chunk generates");
+    let default = generate_chunk(SEED, -4, -4, 64).expect("the chunk generates");
+
+    for (tile, default_tile) in chunk.map_data.iter().zip(default.map_data.iter()) {
+        // the table only changes which biome grows, not the land itself
+        assert_eq!(tile.height, default_tile.height);
+
+        match tile.tile_type {
+            Biomes::SaltWater | Biomes::Beach => assert_eq!(tile.tile_type, default_tile.tile_type),
+            biome if tile.height >= 0.5 => assert_eq!(biome, highlands),
+            biome => assert_eq!(biome, Biomes::Grassland),
+        }
+    }
+
+    assert!(chunk.map_data.iter().any(|tile| tile.tile_type == highlands), "the chunk has highlands on it");
+}
+
+#[test]
+fn empty_chunks_are_rejected() {
+    assert!(generate_chunk(SEED, 0, 0, 0).is_err());
+    assert!(generate_chunk("", 0, 0, 16).is_err());
+}
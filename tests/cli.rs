@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const TABLE: &str = r#"{
+    "temperature_bands": 4,
+    "moisture_bands": 6,
+    "table": [
+        ["SubtropicalDesert", "Grassland", "TropicalSeasonalForest", "TropicalSeasonalForest", "TropicalRainForest", "TropicalRainForest"],
+        ["TemperateDesert", "Grassland", "Grassland", "TemperateDeciduousForest", "TemperateDeciduousForest", "TemperateRainForest"],
+        ["TemperateDesert", "TemperateDesert", "Shrubland", "Shrubland", "Taiga", "Taiga"],
+        ["Scorched", "Bare", "Tundra", "Snow", "Snow", "Snow"]
+    ],
+    "biomes": [
+        { "biome": "Beach", "name": "Sandbar", "symbol": ":", "colour": [240, 220, 160] }
+    ],
+    "custom": [
+        { "name": "Swamp", "symbol": "%", "colour": [90, 110, 70], "rule": { "moisture": [4, 6], "height": [0.0, 0.4] } },
+        { "name": "Highlands", "symbol": "h", "colour": [120, 100, 80], "rule": { "height": [0.6, 1.0] } }
+    ]
+}"#;
+
+// a directory of its own for every test, so they can run side by side
+fn output_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("whittaker_cli_{}", name));
+
+    fs::create_dir_all(&dir).expect("the output directory is created");
+    dir
+}
+
+fn run(dir: &Path, args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_whittaker_map_generator"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .expect("the generator runs");
+
+    assert!(output.status.success(), "{:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    output
+}
+
+// the name of every biome in the stats of a map
+fn biome_names(output: &Output) -> Vec<String> {
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).expect("the stats are json");
+
+    stats["biomes"]
+        .as_array()
+        .expect("the stats list the biomes")
+        .iter()
+        .map(|biome| biome["name"].as_str().expect("a biome has a name").to_string())
+        .collect()
+}
+
+#[test]
+fn stats_of_a_saved_map_keep_its_biome_table() {
+    let dir = output_dir("stats");
+
+    fs::write(dir.join("table.json"), TABLE).expect("the table is written");
+    run(&dir, &["--seed", "cli", "--size", "150", "--biomes", "table.json", "--output", "map.json"]);
+
+    let generated = run(&dir, &["--seed", "cli", "--size", "150", "--biomes", "table.json", "stats", "--json"]);
+    let loaded = run(&dir, &["stats", "--map", "map.json", "--json"]);
+    let text = run(&dir, &["stats", "--map", "map.json"]);
+
+    fs::remove_dir_all(&dir).ok();
+
+    let names = biome_names(&loaded);
+
+    // the custom and restyled biomes keep their own names without --biomes
+    assert_eq!(names, biome_names(&generated));
+
+    for name in ["Swamp", "Highlands", "Sandbar"] {
+        assert!(names.contains(&name.to_string()), "no {} in {:?}", name, names);
+    }
+
+    assert!(!names.iter().any(|name| name == "Custom" || name == "Beach"), "{:?}", names);
+    assert!(String::from_utf8_lossy(&text.stdout).contains("Swamp"));
+}
+
+#[test]
+fn biomes_restyle_a_saved_map() {
+    let dir = output_dir("restyle");
+    let restyled = TABLE.replace(r#""name": "Swamp""#, r#""name": "Marsh""#);
+
+    fs::write(dir.join("table.json"), TABLE).expect("the table is written");
+    fs::write(dir.join("restyled.json"), restyled).expect("the table is written");
+    run(&dir, &["--seed", "cli", "--size", "150", "--biomes", "table.json", "--output", "map.bin"]);
+
+    let loaded = run(&dir, &["--biomes", "restyled.json", "stats", "--map", "map.bin", "--json"]);
+
+    fs::remove_dir_all(&dir).ok();
+
+    let names = biome_names(&loaded);
+
+    assert!(names.contains(&"Marsh".to_string()) && !names.contains(&"Swamp".to_string()), "{:?}", names);
+}
@@ -0,0 +1,32 @@
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::biomes::Biome;
+use whittaker_map_generator::grid::Grid;
+use whittaker_map_generator::steppers::map_position::MapPosition;
+use whittaker_map_generator::MapData;
+
+/// A 10x10 sea with a 5x5 island around a 3x3 lake, a 1 tile island, and a river 4 tiles long out in the sea.
+pub fn hand_made_map() -> MapData {
+    let mut map_data = Grid::new(10, 10, Biome::new(Biomes::SaltWater));
+
+    for position in map_data.positions().collect::<Vec<MapPosition>>() {
+        let (x, y) = (position.x, position.y);
+
+        let tile_type = if (2..=4).contains(&x) && (2..=4).contains(&y) {
+            Biomes::FreshWater
+        } else if (1..=5).contains(&x) && (1..=5).contains(&y) {
+            Biomes::Grassland
+        } else if (x, y) == (8, 8) {
+            Biomes::Snow
+        } else if x == 8 && (1..=4).contains(&y) {
+            Biomes::FreshWater
+        } else {
+            continue;
+        };
+
+        map_data[position] = Biome::new(tile_type);
+        map_data[position].height = 0.95;
+        map_data[position].moisture = 3;
+    }
+
+    map_data
+}
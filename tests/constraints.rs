@@ -0,0 +1,147 @@
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::constraints::Constraints;
+use whittaker_map_generator::error::GeneratorError;
+use whittaker_map_generator::generator::Generator;
+use whittaker_map_generator::preset::Preset;
+use whittaker_map_generator::topology::Topology;
+
+const SEED: &str = "constraints";
+
+fn generator(seed: &str) -> Generator {
+    Generator::builder()
+        .seed(seed)
+        .map_size(120)
+        .steppers(100)
+        .steps(150)
+        .build()
+        .expect("the config is valid")
+}
+
+#[test]
+fn the_reported_attempt_reproduces_the_map() {
+    let constraints = Constraints::new().land_ratio(0.45..=0.55).min_lakes(1);
+    let mut generator = generator(SEED);
+    let report = generator.generate_with_constraints(&constraints).expect("a map meets the constraints");
+
+    // 100 steppers cover far too much of a map this small, so they were scaled down on the way
+    assert!(report.attempt > 1 && report.steppers < 100);
+    assert!(constraints.check(generator.get_map_data()).is_empty());
+    assert!((0.45..=0.55).contains(&generator.get_stats().land_ratio));
+    assert_eq!(generator.get_seed(), report.seed);
+
+    // the attempts are the same every time
+    let mut again = self::generator(SEED);
+
+    assert_eq!(again.generate_with_constraints(&constraints).expect("a map meets the constraints"), report);
+
+    // and the report is all it takes to generate the map directly
+    let mut direct = Generator::builder()
+        .seed(report.seed.clone())
+        .map_size(120)
+        .steppers(report.steppers)
+        .steps(150)
+        .build()
+        .expect("the config is valid");
+
+    direct.generate().expect("the map generates");
+
+    for (tile, direct_tile) in generator.get_map_data().iter().zip(direct.get_map_data().iter()) {
+        assert_eq!(tile.tile_type, direct_tile.tile_type);
+    }
+}
+
+#[test]
+fn islands_and_biomes_are_constrained() {
+    let constraints = Constraints::new()
+        .min_islands(8)
+        .min_largest_island(100)
+        .require_biome(Biomes::Snow)
+        .require_biome(Biomes::Grassland);
+    let mut generator = Generator::builder()
+        .seed(SEED)
+        .map_size(200)
+        .preset(Preset::Archipelago)
+        .build()
+        .expect("the config is valid");
+
+    let report = generator.generate_with_constraints(&constraints).expect("a map meets the constraints");
+    let map_data = generator.get_map_data();
+
+    assert!(report.attempt >= 1);
+    assert!(map_data.iter().any(|tile| tile.tile_type == Biomes::Snow));
+    assert!(map_data.iter().any(|tile| tile.tile_type == Biomes::Grassland));
+}
+
+#[test]
+fn unmet_constraints_give_the_seed_back() {
+    let constraints = Constraints::new().min_islands(500).max_attempts(3);
+    let mut generator = generator(SEED);
+
+    match generator.generate_with_constraints(&constraints) {
+        Err(GeneratorError::UnmetConstraints(message)) => assert!(message.contains("3 attempts")),
+        other => panic!("expected unmet constraints, got {:?}", other),
+    }
+
+    assert_eq!(generator.get_seed(), SEED);
+}
+
+#[test]
+fn attempts_without_sea_are_retried() {
+    let crowded = || {
+        Generator::builder()
+            .seed(SEED)
+            .map_size(40)
+            .steppers(400)
+            .steps(300)
+            .topology(Topology::Toroidal)
+            .build()
+            .expect("the config is valid")
+    };
+
+    // so many steppers on a small map which wraps all around leave no sea at all on the first attempt
+    assert!(matches!(crowded().generate(), Err(GeneratorError::DegenerateMap(_))));
+
+    let constraints = Constraints::new().land_ratio(0.2..=0.8);
+    let mut generator = crowded();
+    let report = generator.generate_with_constraints(&constraints).expect("a map meets the constraints");
+
+    assert!(report.attempt > 1 && report.steppers < 400);
+    assert!(constraints.check(generator.get_map_data()).is_empty());
+
+    // when every attempt is degenerate the search gives up like any other and the generator gets its seed back
+    let mut generator = crowded();
+
+    match generator.generate_with_constraints(&Constraints::new().min_lakes(1).max_attempts(2)) {
+        Err(GeneratorError::UnmetConstraints(message)) => assert!(message.contains("no sea")),
+        other => panic!("expected unmet constraints, got {:?}", other),
+    }
+
+    assert_eq!(generator.get_seed(), SEED);
+}
+
+#[test]
+fn broken_configs_stop_the_search_and_give_the_seed_back() {
+    let mut generator = Generator::new(SEED.to_string(), 2, 2);
+
+    assert!(matches!(
+        generator.generate_with_constraints(&Constraints::new().min_lakes(1)),
+        Err(GeneratorError::InvalidConfig(_))
+    ));
+    assert_eq!(generator.get_seed(), SEED);
+}
+
+#[test]
+fn invalid_constraints_are_rejected() {
+    let mut generator = generator(SEED);
+
+    for constraints in [
+        Constraints::new().max_attempts(0),
+        Constraints::new().land_ratio(0.8..=0.2),
+        Constraints::new().land_ratio(0.5..=1.5),
+    ] {
+        assert!(matches!(
+            generator.generate_with_constraints(&constraints),
+            Err(GeneratorError::InvalidConfig(_))
+        ));
+    }
+}
@@ -0,0 +1,172 @@
+use std::fs;
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::biomes::Biome;
+use whittaker_map_generator::biomes::table::{BiomeDefinition, BiomeTable, PlacementRule};
+use whittaker_map_generator::error::GeneratorError;
+use whittaker_map_generator::generator::Generator;
+use whittaker_map_generator::serialization::MapFormat;
+
+fn swamp() -> BiomeDefinition {
+    BiomeDefinition {
+        name: "Swamp".to_string(),
+        symbol: "%".to_string(),
+        colour: [90, 110, 70],
+        rule: Some(PlacementRule {
+            moisture: Some([5, 6]),
+            height: Some([0.0, 0.4]),
+            ..PlacementRule::default()
+        }),
+    }
+}
+
+fn savanna() -> BiomeDefinition {
+    BiomeDefinition {
+        name: "Savanna".to_string(),
+        symbol: "v".to_string(),
+        colour: [210, 190, 110],
+        rule: None,
+    }
+}
+
+#[test]
+fn registered_biomes_are_placed_by_their_rule_and_the_table() {
+    let mut table = BiomeTable::default();
+    let swamp = table.register(swamp()).expect("the biome is registered");
+    let savanna = table.register(savanna()).expect("the biome is registered");
+
+    // the temperate grassland of the whittaker diagram
+    table.set_biome(2, 2, savanna).expect("the cell is in the table");
+    table.set_biome(2, 3, savanna).expect("the cell is in the table");
+
+    let mut generator = Generator::builder()
+        .seed("custom biomes")
+        .map_size(200)
+        .rivers(6)
+        .biome_table(table)
+        .build()
+        .expect("the config is valid");
+
+    generator.generate().expect("the map generates");
+
+    let table = generator.get_biome_table();
+    let rule = table.get_definition(swamp).and_then(|definition| definition.rule.clone()).expect("swamps have a rule");
+    let map_data = generator.get_map_data();
+    let swamps: Vec<_> = map_data.iter().filter(|tile| tile.tile_type == swamp).collect();
+
+    assert!(!swamps.is_empty(), "no swamps were placed");
+    assert!(swamps.iter().all(|tile| rule.matches(tile)));
+    // every tile which matches the rule and would otherwise be a whittaker biome is a swamp
+    assert!(map_data
+        .iter()
+        .filter(|tile| tile.tile_type.is_whittaker() && tile.tile_type != swamp)
+        .all(|tile| !rule.matches(tile)));
+    assert!(map_data.iter().any(|tile| tile.tile_type == savanna), "no savanna was placed");
+
+    assert_eq!(table.get_name(swamp), "Swamp");
+    assert_eq!(table.get_symbol(savanna), "v");
+    assert_eq!(table.get_colour(swamp).0, [90, 110, 70]);
+
+    let file_name = std::env::temp_dir().join("custom_biomes.txt");
+    generator.output_file(file_name.to_string_lossy().to_string()).expect("the text map writes");
+    let text = fs::read_to_string(&file_name).expect("the text map reads");
+    fs::remove_file(&file_name).ok();
+
+    assert!(text.contains('%') && text.contains('v'));
+}
+
+#[test]
+fn maps_with_custom_biomes_round_trip() {
+    let mut table = BiomeTable::default();
+    table.register(swamp()).expect("the biome is registered");
+
+    let mut generator = Generator::builder()
+        .seed("custom biomes")
+        .map_size(120)
+        .biome_table(table)
+        .build()
+        .expect("the config is valid");
+
+    generator.generate().expect("the map generates");
+
+    for format in [MapFormat::Json, MapFormat::Binary] {
+        let mut bytes = Vec::new();
+        generator.save(&mut bytes, format).expect("the map saves");
+
+        let loaded = Generator::load(bytes.as_slice(), format).expect("the map loads");
+
+        assert_eq!(loaded.get_map_data(), generator.get_map_data());
+        // the custom biomes keep their names and colours
+        assert_eq!(loaded.get_biome_table(), generator.get_biome_table());
+        assert_eq!(loaded.get_biome_table().get_name(Biomes::Custom(0)), "Swamp");
+    }
+}
+
+#[test]
+fn debug_colours_come_from_the_table() {
+    let json = r#"{
+        "temperature_bands": 1, "moisture_bands": 1, "table": [["Grassland"]],
+        "biomes": [
+            { "biome": "SaltWater", "name": "Ocean", "symbol": "~", "colour": [1, 2, 3] },
+            { "biome": "FreshWater", "name": "River", "symbol": "=", "colour": [4, 5, 6] }
+        ]
+    }"#;
+    let table = BiomeTable::from_reader(json.as_bytes()).expect("the table parses");
+
+    assert_eq!(Biome::new(Biomes::SaltWater).get_elevation_colour(&table).0, [1, 2, 3]);
+    assert_eq!(Biome::new(Biomes::FreshWater).get_temperature_colour(&table).0, [4, 5, 6]);
+    assert_eq!(
+        Biome::new(Biomes::Grassland).get_elevation_colour(&table),
+        Biome::get_elevation_band_colour(1)
+    );
+}
+
+#[test]
+fn custom_biomes_load_from_json() {
+    let json = r#"{
+        "temperature_bands": 1,
+        "moisture_bands": 2,
+        "table": [["Grassland", { "Custom": 0 }]],
+        "custom": [
+            { "name": "Mangrove", "symbol": "m", "colour": [40, 90, 60] },
+            { "name": "Volcanic", "symbol": "^", "colour": [60, 20, 20], "rule": { "height": [0.9, 1.0] } }
+        ]
+    }"#;
+    let table = BiomeTable::from_reader(json.as_bytes()).expect("the table parses");
+
+    assert_eq!(table.get_biome(0.5, 6), Biomes::Custom(0));
+    assert_eq!(table.get_name(Biomes::Custom(1)), "Volcanic");
+    assert_eq!(table.custom_biomes().count(), 2);
+
+    let mut json = Vec::new();
+    table.to_writer(&mut json).expect("the table serializes");
+
+    assert_eq!(BiomeTable::from_reader(json.as_slice()).expect("the table parses"), table);
+}
+
+#[test]
+fn unknown_custom_biomes_are_rejected() {
+    let json = r#"{ "temperature_bands": 1, "moisture_bands": 1, "table": [[{ "Custom": 0 }]] }"#;
+    let styled = r#"{
+        "temperature_bands": 1, "moisture_bands": 1, "table": [["Snow"]],
+        "biomes": [{ "biome": { "Custom": 0 }, "name": "a", "symbol": "a", "colour": [0, 0, 0] }],
+        "custom": [{ "name": "a", "symbol": "a", "colour": [0, 0, 0] }]
+    }"#;
+
+    assert!(BiomeTable::from_reader(json.as_bytes()).is_err());
+    assert!(BiomeTable::from_reader(styled.as_bytes()).is_err());
+    assert!(BiomeTable::default().set_biome(1, 1, Biomes::Custom(0)).is_err());
+    assert!(BiomeTable::default().set_biome(5, 1, Biomes::Snow).is_err());
+}
+
+#[test]
+fn registering_too_many_biomes_is_an_error() {
+    let mut table = BiomeTable::default();
+
+    for _ in 0..65536 {
+        table.register(swamp()).expect("the biome is registered");
+    }
+
+    assert_eq!(table.custom_biomes().last().map(|(biome, _)| biome), Some(Biomes::Custom(u16::MAX)));
+    assert!(matches!(table.register(swamp()), Err(GeneratorError::InvalidConfig(_))));
+    assert_eq!(table.custom_biomes().count(), 65536);
+}
@@ -0,0 +1,75 @@
+use rand::Rng;
+use rand_pcg::Pcg64;
+use rand_seeder::Seeder;
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::biomes::Biome;
+use whittaker_map_generator::distance::distance_field;
+use whittaker_map_generator::grid::Grid;
+use whittaker_map_generator::topology::Topology;
+use whittaker_map_generator::MapData;
+
+// the distance between two coordinates along an axis, the short way round when the axis wraps
+fn axis_distance(from: i32, to: i32, size: u32, wraps: bool) -> f64 {
+    let distance = (from - to).abs();
+
+    if wraps { distance.min(size as i32 - distance) as f64 } else { distance as f64 }
+}
+
+fn brute_force(map_data: &MapData, biome: Biomes) -> Grid<f32> {
+    let topology = map_data.topology();
+    let targets: Vec<_> = map_data.positions().filter(|position| map_data[*position].tile_type == biome).collect();
+
+    let mut distances = map_data.map(|_| f32::INFINITY);
+
+    for position in map_data.positions() {
+        for target in &targets {
+            let x = axis_distance(position.x, target.x, map_data.width(), topology.wraps_x());
+            let y = axis_distance(position.y, target.y, map_data.height(), topology.wraps_y());
+            let distance = (x * x + y * y).sqrt() as f32;
+
+            if distance < distances[position] {
+                distances[position] = distance;
+            }
+        }
+    }
+
+    distances
+}
+
+#[test]
+fn the_transform_matches_the_nearest_tile_by_brute_force() {
+    let mut rng: Pcg64 = Seeder::from("distance").make_rng();
+
+    for topology in Topology::ALL {
+        for _ in 0..300 {
+            let (width, height) = (rng.gen_range(1..=16), rng.gen_range(1..=16));
+            // from an empty map up to one which is nearly all water
+            let density = rng.gen_range(0.0..0.6);
+            let mut map_data = Grid::new(width, height, Biome::new(Biomes::Placeholder)).with_topology(topology);
+
+            for tile in map_data.iter_mut() {
+                if rng.gen_bool(density) {
+                    tile.tile_type = Biomes::FreshWater;
+                }
+            }
+
+            let distances = distance_field(&map_data, Biomes::FreshWater);
+            let expected = brute_force(&map_data, Biomes::FreshWater);
+
+            for position in map_data.positions() {
+                let (distance, expected) = (distances[position], expected[position]);
+
+                assert!(
+                    distance == expected || (distance - expected).abs() < 1e-4,
+                    "{} away instead of {} at {:?} on a {}x{} {} map",
+                    distance,
+                    expected,
+                    position,
+                    width,
+                    height,
+                    topology
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::biomes::Biome;
+use whittaker_map_generator::builder::GeneratorBuilder;
+use whittaker_map_generator::generator::Generator;
+use whittaker_map_generator::grid::Grid;
+use whittaker_map_generator::steppers::lake::Lake;
+use whittaker_map_generator::steppers::map_position::MapPosition;
+use whittaker_map_generator::steppers::ridge::Ridge;
+use whittaker_map_generator::steppers::StepperBehaviour;
+
+fn builder(seed: &str) -> GeneratorBuilder {
+    Generator::builder()
+        .seed(seed)
+        .map_size(150)
+        .steppers(150)
+        .steps(200)
+        .rivers(0)
+}
+
+#[test]
+fn lakes_never_touch_the_sea() {
+    let mut lakes = 0;
+
+    for attempt in 0..30 {
+        let mut generator = builder(&format!("lakes {}", attempt))
+            .add_stepper(Box::new(Lake::create(3)), 20, 40)
+            .build()
+            .expect("the config is valid");
+
+        generator.generate().expect("the map generates");
+
+        let map_data = generator.get_map_data();
+
+        for (position, _) in map_data.enumerate().filter(|(_, tile)| tile.tile_type == Biomes::FreshWater) {
+            lakes += 1;
+
+            assert!(
+                map_data.adjacent(position).all(|neighbour| map_data[neighbour].tile_type != Biomes::SaltWater),
+                "the lake at {:?} touches the sea",
+                position
+            );
+        }
+    }
+
+    assert!(lakes > 0, "no lakes were dug");
+}
+
+#[test]
+fn ridges_raise_the_land_around_their_crest() {
+    let mut map_data = Grid::new(20, 20, Biome::new(Biomes::Placeholder));
+    let crest = MapPosition { x: 10, y: 10 };
+
+    map_data[MapPosition { x: 12, y: 10 }].tile_type = Biomes::SaltWater;
+    map_data[MapPosition { x: 10, y: 8 }].height = 0.9;
+
+    Ridge::create(0.8, 4).on_step(&mut map_data, crest, 1, 0);
+
+    assert_eq!(map_data[crest].height, 0.8);
+    assert!(map_data[MapPosition { x: 11, y: 10 }].height < map_data[crest].height);
+    assert!(map_data[MapPosition { x: 9, y: 11 }].height > 0.0);
+
+    // the sea is left alone, land already higher than the slope keeps its height, and the slopes end at the radius
+    assert_eq!(map_data[MapPosition { x: 12, y: 10 }].height, 0.0);
+    assert_eq!(map_data[MapPosition { x: 10, y: 8 }].height, 0.9);
+    assert_eq!(map_data[MapPosition { x: 14, y: 10 }].height, 0.0);
+    assert_eq!(map_data[MapPosition { x: 15, y: 10 }].height, 0.0);
+}
@@ -0,0 +1,76 @@
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::generator::{Config, Generator};
+use whittaker_map_generator::grid::Grid;
+use whittaker_map_generator::shape::Shape;
+use whittaker_map_generator::topology::Topology;
+
+#[test]
+fn hexes_are_adjacent_to_each_other_both_ways() {
+    for topology in Topology::ALL {
+        let grid = Grid::new(8, 6, ()).with_topology(topology).with_shape(Shape::Hex);
+
+        for position in grid.positions() {
+            let adjacent: Vec<_> = grid.adjacent(position).collect();
+
+            assert!(adjacent.len() <= 6, "{:?} has {} hexes around it", position, adjacent.len());
+
+            for neighbour in adjacent {
+                assert!(
+                    grid.adjacent(neighbour).any(|back| back == position),
+                    "{:?} is next to {:?} but not the other way round on a {} map",
+                    position,
+                    neighbour,
+                    topology
+                );
+            }
+        }
+
+        if topology == Topology::Toroidal {
+            assert!(grid.positions().all(|position| grid.adjacent(position).count() == 6));
+        }
+    }
+}
+
+#[test]
+fn hex_maps_generate_without_gaps() {
+    let mut generator = Generator::builder()
+        .seed("hexes")
+        .map_size(120)
+        .shape(Shape::Hex)
+        .build()
+        .expect("the config is valid");
+
+    generator.generate().expect("the map generates");
+
+    let map_data = generator.get_map_data();
+
+    assert_eq!(map_data.shape(), Shape::Hex);
+    assert!(map_data.iter().all(|tile| !matches!(tile.tile_type, Biomes::Void | Biomes::Placeholder)));
+}
+
+#[test]
+fn hex_maps_which_wrap_north_to_south_need_an_even_height() {
+    let result = Generator::builder()
+        .seed("hexes")
+        .width(60)
+        .height(61)
+        .shape(Shape::Hex)
+        .topology(Topology::Toroidal)
+        .build();
+
+    let error = result.err().map(|error| error.to_string()).expect("an odd height is rejected");
+
+    // a map reconfigured after it is built is held to the same rule
+    let mut generator = Generator::builder()
+        .seed("hexes")
+        .width(60)
+        .height(60)
+        .shape(Shape::Hex)
+        .topology(Topology::Toroidal)
+        .build()
+        .expect("the config is valid");
+
+    generator.set(Config::Height(61));
+
+    assert_eq!(generator.generate().err().map(|error| error.to_string()), Some(error));
+}
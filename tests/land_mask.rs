@@ -0,0 +1,82 @@
+use image::{DynamicImage, GrayImage, Luma};
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::generator::Generator;
+use whittaker_map_generator::grid::Grid;
+use whittaker_map_generator::land_mask::LandMask;
+use whittaker_map_generator::MapData;
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 100;
+
+/// A wide ellipse with a bay bitten out of its east coast, at half the resolution of the map.
+fn silhouette() -> GrayImage {
+    GrayImage::from_fn(WIDTH / 2, HEIGHT / 2, |x, y| {
+        let (dx, dy) = ((x as f64 - 40.0) / 32.0, (y as f64 - 25.0) / 18.0);
+        let bite = (50..80).contains(&x) && (18..32).contains(&y);
+
+        Luma([if dx * dx + dy * dy < 1.0 && !bite { 255 } else { 0 }])
+    })
+}
+
+fn generate(land_mask: LandMask, coast_steppers: u32) -> MapData {
+    let mut generator = Generator::builder()
+        .seed("land mask")
+        .width(WIDTH)
+        .height(HEIGHT)
+        .land_mask(land_mask)
+        .coast_steppers(coast_steppers)
+        .build()
+        .expect("the config is valid");
+
+    generator.generate().expect("the map generates");
+    generator.get_map_data().clone()
+}
+
+// the share of tiles on which the map and the mask disagree about where the sea is
+fn mismatch(map_data: &MapData, land_mask: &LandMask) -> f64 {
+    let wrong = map_data
+        .enumerate()
+        .filter(|(position, tile)| {
+            (tile.tile_type == Biomes::SaltWater) == land_mask.is_land(*position, WIDTH, HEIGHT)
+        })
+        .count();
+
+    wrong as f64 / map_data.len() as f64
+}
+
+#[test]
+fn the_land_follows_the_mask() {
+    let land_mask = LandMask::from_image(&DynamicImage::ImageLuma8(silhouette())).expect("the mask is valid");
+    let map_data = generate(land_mask.clone(), 0);
+
+    assert!(mismatch(&map_data, &land_mask) < 0.01, "the map strays from the mask");
+    // the passes after the landmass still run, so the land is covered in biomes and the coast in beaches
+    assert!(map_data.iter().all(|tile| !matches!(tile.tile_type, Biomes::Void | Biomes::Placeholder)));
+    assert!(map_data.iter().any(|tile| tile.tile_type.is_whittaker()));
+}
+
+#[test]
+fn coast_steppers_roughen_up_the_coast() {
+    let land_mask = LandMask::from_image(&DynamicImage::ImageLuma8(silhouette())).expect("the mask is valid");
+    let exact = generate(land_mask.clone(), 0);
+    let rough = generate(land_mask.clone(), 40);
+    let changed = exact.iter().zip(rough.iter()).any(|(tile, other)| {
+        (tile.tile_type == Biomes::SaltWater) != (other.tile_type == Biomes::SaltWater)
+    });
+
+    assert!(changed, "the coast steppers did not change the coast");
+    assert!(mismatch(&rough, &land_mask) < 0.2, "the coast steppers lost the shape of the mask");
+}
+
+#[test]
+fn masks_load_from_images() {
+    let file_name = std::env::temp_dir().join("land_mask.png");
+    silhouette().save(&file_name).expect("the mask saves");
+
+    let loaded = LandMask::load(&file_name).expect("the mask loads");
+    std::fs::remove_file(&file_name).ok();
+
+    assert_eq!(loaded, LandMask::from_image(&DynamicImage::ImageLuma8(silhouette())).expect("the mask is valid"));
+    assert_eq!((loaded.width(), loaded.height()), (WIDTH / 2, HEIGHT / 2));
+    assert!(LandMask::new(Grid::new(0, 0, true)).is_err());
+}
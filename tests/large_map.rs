@@ -0,0 +1,71 @@
+use std::thread;
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::generator::Generator;
+use whittaker_map_generator::steppers::map_position::MapPosition;
+use whittaker_map_generator::steppers::StepperBehaviour;
+use whittaker_map_generator::MapData;
+
+const MAP_SIZE: u32 = 2048;
+// far below the 8 MiB main thread stack, a cleanup that recurses per tile runs out of room long before the end
+const STACK_SIZE: usize = 256 * 1024;
+
+/// Raises a block of land with a single, one tile wide channel snaking back and forth through it. The cleanup
+/// unravels the channel one tile at a time from its dead end, which is the longest chain a map can produce.
+struct Serpentine {}
+
+impl StepperBehaviour for Serpentine {
+    fn on_step(&self, _map_data: &mut MapData, _current_pos: MapPosition, _steps_left: u32, _last_direction_steps: u32) -> bool {
+        true
+    }
+
+    fn on_last_step(&self, map_data: &mut MapData, _current_pos: MapPosition) {
+        let margin = (map_data.width() / 8) as i32;
+        let (left, right) = (margin, map_data.width() as i32 - margin - 1);
+        let (top, bottom) = (margin, map_data.height() as i32 - margin - 1);
+
+        for y in top..=bottom {
+            for x in left..=right {
+                map_data[MapPosition { x, y }].tile_type = Biomes::Placeholder;
+            }
+        }
+
+        // keep at least two tiles of land around the channel, thinner land is removed as a straggler
+        for (turn, y) in (top + 2..bottom - 4).step_by(3).enumerate() {
+            for x in left + 2..right - 1 {
+                map_data[MapPosition { x, y }].tile_type = Biomes::Void;
+            }
+
+            // join the end of this row to the next one, alternating sides
+            let x = if turn % 2 == 0 { right - 2 } else { left + 2 };
+
+            for y in y + 1..y + 3 {
+                map_data[MapPosition { x, y }].tile_type = Biomes::Void;
+            }
+        }
+    }
+}
+
+#[test]
+fn large_map_generates_on_a_small_stack() {
+    let handle = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(|| {
+            let mut generator = Generator::builder()
+                .seed("large map")
+                .map_size(MAP_SIZE)
+                .add_stepper(Box::new(Serpentine {}), 1, 1)
+                .build()
+                .expect("the config is valid");
+
+            generator.generate().map(|_| ()).expect("the map generates");
+
+            // the channel is cleaned up into land instead of being left as a lake
+            let channel = MapPosition { x: MAP_SIZE as i32 / 2, y: MAP_SIZE as i32 / 8 + 2 };
+            let tile_type = generator.get_map_data()[channel].tile_type;
+
+            assert!(!matches!(tile_type, Biomes::FreshWater | Biomes::SaltWater), "the channel is {:?}", tile_type);
+        })
+        .expect("the thread spawns");
+
+    handle.join().expect("generation does not overflow the stack");
+}
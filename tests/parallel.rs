@@ -0,0 +1,50 @@
+use rayon::ThreadPoolBuilder;
+use whittaker_map_generator::generator::Generator;
+use whittaker_map_generator::serialization::MapFormat;
+use whittaker_map_generator::steppers::lake::Lake;
+use whittaker_map_generator::steppers::ridge::Ridge;
+use whittaker_map_generator::shape::Shape;
+use whittaker_map_generator::topology::Topology;
+
+fn generate(seed: &str, topology: Topology, shape: Shape, parallel: bool) -> Vec<u8> {
+    let mut generator = Generator::builder()
+        .seed(seed)
+        .map_size(400)
+        .rivers(4)
+        .add_stepper(Box::new(Ridge::create(0.5, 6)), 6, 120)
+        .add_stepper(Box::new(Lake::create(3)), 4, 40)
+        .topology(topology)
+        .shape(shape)
+        .parallel(parallel)
+        .build()
+        .expect("the config is valid");
+
+    generator.generate().expect("the map generates");
+
+    let mut bytes = Vec::new();
+    generator.save(&mut bytes, MapFormat::Binary).expect("the map serializes");
+
+    bytes
+}
+
+#[test]
+fn parallel_generation_matches_single_threaded() {
+    // a fixed number of threads, so the work is split up even on a machine with a single core
+    let pool = ThreadPoolBuilder::new().num_threads(4).build().expect("the thread pool builds");
+
+    for seed in ["parallel", "threads", "deterministic"] {
+        for topology in Topology::ALL {
+            for shape in Shape::ALL {
+                let parallel = pool.install(|| generate(seed, topology, shape, true));
+
+                assert!(
+                    parallel == generate(seed, topology, shape, false),
+                    "the {} {} maps for seed {:?} differ",
+                    topology,
+                    shape,
+                    seed
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::generator::Generator;
+use whittaker_map_generator::preset::Preset;
+use whittaker_map_generator::regions::{RegionKind, Regions};
+use whittaker_map_generator::steppers::map_position::MapPosition;
+use whittaker_map_generator::MapData;
+
+const SIZE: u32 = 300;
+
+fn generate(preset: Preset) -> MapData {
+    let mut generator = Generator::builder()
+        .seed("presets")
+        .map_size(SIZE)
+        .preset(preset)
+        .build()
+        .expect("the config is valid");
+
+    generator.generate().expect("the map generates");
+    generator.get_map_data().clone()
+}
+
+// the number of tiles on each separate piece of land, largest first
+fn landmasses(map_data: &MapData) -> Vec<usize> {
+    let mut sizes: Vec<usize> =
+        Regions::landmasses(map_data).of_kind(RegionKind::Island).map(|island| island.area).collect();
+
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    sizes
+}
+
+// ignores the specks of land the passes after the landmass leave behind
+fn islands(sizes: &[usize]) -> usize {
+    sizes.iter().filter(|size| **size >= 20).count()
+}
+
+#[test]
+fn presets_shape_the_world_differently() {
+    let archipelago = landmasses(&generate(Preset::Archipelago));
+    let pangaea = landmasses(&generate(Preset::Pangaea));
+    let continents = landmasses(&generate(Preset::Continents));
+
+    let pangaea_land: usize = pangaea.iter().sum();
+
+    assert!(pangaea[0] as f64 > pangaea_land as f64 * 0.95, "pangaea is one supercontinent");
+    assert!(islands(&continents) >= 2, "the continents are separated by sea");
+    assert!(continents[0] < pangaea[0], "each continent is smaller than the supercontinent");
+    assert!(islands(&archipelago) > islands(&continents) * 2, "an archipelago has many more islands");
+    assert!(archipelago[0] < continents[0], "the islands of an archipelago are smaller than continents");
+}
+
+#[test]
+fn ring_presets_leave_the_middle_of_the_map_open() {
+    let middle = MapPosition { x: SIZE as i32 / 2, y: SIZE as i32 / 2 };
+    let inland_sea = generate(Preset::InlandSea);
+    let atoll_ring = generate(Preset::AtollRing);
+
+    // the land closes around the inland sea, which leaves it cut off from the ocean
+    assert_eq!(inland_sea[middle].tile_type, Biomes::FreshWater);
+    assert!(!atoll_ring[middle].is_land());
+    assert!(islands(&landmasses(&atoll_ring)) >= 5, "the atolls are separate islands");
+}
+
+#[test]
+fn steppers_and_steps_override_the_preset() {
+    let build = |steppers: Option<u32>| {
+        let mut builder = Generator::builder().seed("presets").map_size(100).preset(Preset::Pangaea);
+
+        if let Some(steppers) = steppers {
+            builder = builder.steppers(steppers).steps(Preset::Pangaea.steps());
+        }
+
+        let mut generator = builder.build().expect("the config is valid");
+
+        generator.generate().expect("the map generates");
+        generator.get_map_data().iter().filter(|tile| tile.is_land()).count()
+    };
+
+    assert_eq!(build(None), build(Some(Preset::Pangaea.steppers())));
+    assert!(build(Some(5)) < build(None));
+}
+
+#[test]
+fn presets_parse_from_their_names() {
+    for preset in Preset::ALL {
+        assert_eq!(preset.get_name().parse::<Preset>(), Ok(preset));
+    }
+
+    assert_eq!("Inland_Sea".parse::<Preset>(), Ok(Preset::InlandSea));
+    assert!("supercontinent".parse::<Preset>().is_err());
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use common::hand_made_map;
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::biomes::Biome;
+use whittaker_map_generator::generator::Generator;
+use whittaker_map_generator::grid::Grid;
+use whittaker_map_generator::regions::{RegionKind, Regions};
+use whittaker_map_generator::shape::Shape;
+use whittaker_map_generator::steppers::map_position::MapPosition;
+use whittaker_map_generator::topology::Topology;
+
+#[test]
+fn every_tile_is_labelled_with_its_landmass() {
+    let regions = Regions::landmasses(&hand_made_map());
+
+    assert_eq!(regions.len(), 5);
+    assert_eq!(regions.regions().iter().map(|region| region.area).sum::<usize>(), 100);
+
+    let lake = regions.at(MapPosition { x: 3, y: 3 }).expect("the lake is a region");
+
+    assert_eq!(lake.kind, RegionKind::Lake);
+    assert_eq!(lake.area, 9);
+    assert_eq!((lake.min, lake.max), (MapPosition { x: 2, y: 2 }, MapPosition { x: 4, y: 4 }));
+    assert_eq!(lake.centroid, (3.0, 3.0));
+
+    let island = regions.at(MapPosition { x: 1, y: 5 }).expect("the island is a region");
+
+    assert_eq!(island.kind, RegionKind::Island);
+    assert_eq!(island.area, 16);
+    assert_eq!(island.size(), (5, 5));
+    assert_eq!(island.centroid, (3.0, 3.0));
+
+    let river = regions.at(MapPosition { x: 8, y: 2 }).expect("the river is a region");
+
+    assert_eq!(river.kind, RegionKind::River);
+    assert_eq!(river.size(), (1, 4));
+
+    assert_eq!(regions.at(MapPosition { x: 0, y: 0 }).map(|ocean| ocean.kind), Some(RegionKind::Ocean));
+    assert_eq!(regions.of_kind(RegionKind::Island).count(), 2);
+    assert!(regions.at(MapPosition { x: 10, y: 0 }).is_none());
+
+    for region in regions.regions() {
+        assert_eq!(regions.get(region.id), Some(region));
+        assert_eq!(regions.positions(region.id).count(), region.area);
+        assert!(regions.positions(region.id).all(|position| regions.id_at(position) == Some(region.id)));
+    }
+}
+
+#[test]
+fn biome_patches_split_the_map_by_biome() {
+    let regions = Regions::biome_patches(&hand_made_map());
+
+    assert_eq!(regions.len(), 5);
+    assert_eq!(regions.of_kind(RegionKind::BiomePatch(Biomes::FreshWater)).count(), 2);
+    assert_eq!(
+        regions.at(MapPosition { x: 8, y: 8 }).map(|patch| (patch.kind, patch.area)),
+        Some((RegionKind::BiomePatch(Biomes::Snow), 1))
+    );
+}
+
+#[test]
+fn fresh_water_wider_than_a_tile_is_a_lake() {
+    // the kind of a patch of fresh water dug out of a grassland
+    let kind = |shape: Shape, water: &[(i32, i32)]| {
+        let mut map_data = Grid::new(12, 12, Biome::new(Biomes::Grassland)).with_shape(shape);
+
+        for (x, y) in water {
+            map_data[MapPosition { x: *x, y: *y }].tile_type = Biomes::FreshWater;
+        }
+
+        let regions = Regions::landmasses(&map_data);
+        let (x, y) = water[0];
+
+        regions.at(MapPosition { x, y }).map(|region| region.kind)
+    };
+
+    let pond = [(4, 4), (5, 4), (4, 5), (5, 5)];
+    let wide = [(2, 3), (3, 3), (4, 3), (5, 3), (6, 3), (2, 4), (3, 4), (4, 4), (5, 4), (6, 4)];
+    let winding = [(2, 2), (3, 2), (3, 3), (4, 3), (4, 4), (4, 5), (5, 5), (6, 5), (6, 6)];
+
+    assert_eq!(kind(Shape::Square, &pond), Some(RegionKind::Lake));
+    assert_eq!(kind(Shape::Square, &wide), Some(RegionKind::Lake));
+    assert_eq!(kind(Shape::Square, &winding), Some(RegionKind::River));
+
+    // on hexes three tiles around a corner are already two wide, a straight line is not
+    assert_eq!(kind(Shape::Hex, &[(4, 4), (5, 4), (4, 5)]), Some(RegionKind::Lake));
+    assert_eq!(kind(Shape::Hex, &[(2, 4), (3, 4), (4, 4), (5, 4), (6, 4)]), Some(RegionKind::River));
+}
+
+#[test]
+fn regions_across_a_wrapping_edge_stay_in_one_piece() {
+    let mut map_data = Grid::new(10, 10, Biome::new(Biomes::SaltWater)).with_topology(Topology::Cylindrical);
+
+    for x in [8, 9, 0, 1] {
+        for y in [4, 5] {
+            map_data[MapPosition { x, y }].tile_type = Biomes::Grassland;
+        }
+    }
+
+    let regions = Regions::landmasses(&map_data);
+    let island = regions.at(MapPosition { x: 0, y: 4 }).expect("the island is a region");
+
+    assert_eq!(regions.of_kind(RegionKind::Island).count(), 1);
+    assert_eq!(island.area, 8);
+    // the box starts west of the edge and runs past it
+    assert_eq!((island.min, island.max), (MapPosition { x: 8, y: 4 }, MapPosition { x: 11, y: 5 }));
+    assert_eq!(island.size(), (4, 2));
+    assert_eq!(island.centroid, (9.5, 4.5));
+}
+
+#[test]
+fn the_regions_of_a_generated_map() {
+    let mut generator = Generator::builder()
+        .seed("regions")
+        .map_size(120)
+        .steppers(60)
+        .steps(150)
+        .build()
+        .expect("the config is valid");
+
+    generator.generate().expect("the map generates");
+
+    let map_data = generator.get_map_data();
+    let regions = generator.get_regions();
+
+    for (position, tile) in map_data.enumerate() {
+        let region = regions.at(position).expect("every tile is in a region");
+        let is_water = matches!(region.kind, RegionKind::Ocean | RegionKind::Lake | RegionKind::River);
+
+        assert_eq!(is_water, matches!(tile.tile_type, Biomes::SaltWater | Biomes::FreshWater));
+        assert!(region.min.x <= position.x && position.x <= region.max.x);
+        assert!(region.min.y <= position.y && position.y <= region.max.y);
+    }
+
+    assert_eq!(regions.of_kind(RegionKind::Island).count(), generator.get_stats().islands.len());
+    assert!(generator.get_biome_patches().len() >= regions.len());
+}
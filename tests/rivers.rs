@@ -0,0 +1,47 @@
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::generator::Generator;
+
+#[test]
+fn rivers_flow_downhill_from_the_highest_land_into_water() {
+    let mut generator = Generator::builder()
+        .seed("rivers")
+        .map_size(150)
+        .steppers(150)
+        .steps(200)
+        .rivers(6)
+        .build()
+        .expect("the config is valid");
+
+    generator.generate().expect("the map generates");
+
+    let map_data = generator.get_map_data();
+    let rivers = generator.get_rivers();
+    let highest = map_data.iter().map(|tile| tile.elevation).max().unwrap_or_default();
+
+    assert!(!rivers.is_empty(), "the map has rivers");
+
+    for river in rivers {
+        let source = river[0];
+        let mouth = river[river.len() - 1];
+
+        assert_eq!(map_data[source].elevation, highest, "the river springs from the highest land");
+        assert!(river.iter().all(|position| map_data[*position].tile_type == Biomes::FreshWater));
+
+        for pair in river.windows(2) {
+            assert!(map_data.adjacent(pair[0]).any(|neighbour| neighbour == pair[1]), "the river is unbroken");
+            assert!(map_data[pair[1]].height <= map_data[pair[0]].height, "the river never flows uphill");
+        }
+
+        // it flows into the sea, a lake or another river, or ends in a sink where nothing around it is lower
+        let flows_into_water = map_data.adjacent(mouth).any(|neighbour| {
+            !river.contains(&neighbour)
+                && matches!(map_data[neighbour].tile_type, Biomes::SaltWater | Biomes::FreshWater)
+        });
+        let in_a_sink = map_data
+            .adjacent(mouth)
+            .filter(|neighbour| !river.contains(neighbour))
+            .all(|neighbour| map_data[neighbour].height > map_data[mouth].height);
+
+        assert!(flows_into_water || in_a_sink, "the river at {:?} ends in the middle of nowhere", mouth);
+    }
+}
@@ -0,0 +1,49 @@
+use whittaker_map_generator::generator::Generator;
+use whittaker_map_generator::serialization::MapFormat;
+use whittaker_map_generator::topology::Topology;
+use whittaker_map_generator::MapData;
+
+fn generate() -> Generator {
+    let mut generator = Generator::builder()
+        .seed("round trip")
+        .width(90)
+        .height(60)
+        .steppers(80)
+        .steps(150)
+        .rivers(3)
+        .topology(Topology::Cylindrical)
+        .build()
+        .expect("the config is valid");
+
+    generator.generate().expect("the map generates");
+    generator
+}
+
+#[test]
+fn maps_round_trip_exactly_in_every_format() {
+    let generator = generate();
+
+    for format in [MapFormat::Json, MapFormat::Binary] {
+        let mut bytes = Vec::new();
+
+        generator.save(&mut bytes, format).expect("the map is written");
+
+        let loaded = Generator::load(bytes.as_slice(), format).expect("the map is read");
+
+        // every tile comes back as it was, distances, heights and climate included
+        assert_eq!(loaded.get_map_data(), generator.get_map_data(), "the {:?} map differs", format);
+        assert_eq!(loaded.get_seed(), generator.get_seed());
+        assert_eq!(loaded.get_topology(), Topology::Cylindrical);
+        assert_eq!(&MapData::from_reader(bytes.as_slice(), format).expect("the map is read"), generator.get_map_data());
+    }
+}
+
+#[test]
+fn broken_maps_are_rejected() {
+    let mut bytes = Vec::new();
+
+    generate().save(&mut bytes, MapFormat::Binary).expect("the map is written");
+
+    assert!(MapData::from_reader(&bytes[..bytes.len() / 2], MapFormat::Binary).is_err());
+    assert!(MapData::from_reader(&b"{\"seed\":\"x\",\"map_data\":{\"width\":2,\"height\":2,\"cells\":[]}}"[..], MapFormat::Json).is_err());
+}
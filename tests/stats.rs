@@ -0,0 +1,75 @@
+mod common;
+
+use common::hand_made_map;
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::biomes::table::BiomeTable;
+use whittaker_map_generator::generator::Generator;
+use whittaker_map_generator::stats::MapStats;
+
+#[test]
+fn the_stats_of_a_hand_made_map() {
+    let stats = MapStats::new("hand made", &hand_made_map(), &BiomeTable::default());
+
+    assert_eq!(stats.seed, "hand made");
+    assert_eq!((stats.width, stats.height), (10, 10));
+    assert!((stats.land_ratio - 0.17).abs() < 1e-6);
+    assert!((stats.water_ratio - 0.83).abs() < 1e-6);
+    assert_eq!(stats.islands, vec![16, 1]);
+    // the river is fresh water too, but it is only a tile wide
+    assert_eq!(stats.lakes, vec![9]);
+    // the outside of the 5x5 island and the 1 tile island, the shore of the lake is not coast
+    assert_eq!(stats.coastline, 24);
+    assert_eq!(stats.elevation, vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 17]);
+    assert_eq!(stats.moisture, vec![0, 0, 17, 0, 0, 0]);
+
+    let biomes: Vec<(Biomes, usize)> = stats.biomes.iter().map(|biome| (biome.biome, biome.tiles)).collect();
+
+    assert_eq!(
+        biomes,
+        vec![(Biomes::SaltWater, 70), (Biomes::Grassland, 16), (Biomes::FreshWater, 13), (Biomes::Snow, 1)]
+    );
+    assert_eq!(stats.biomes[0].name, "Salt Water");
+    assert!((stats.biomes[0].percentage - 70.0).abs() < 1e-4);
+}
+
+#[test]
+fn the_stats_of_a_generated_map_add_up() {
+    let mut generator = Generator::builder()
+        .seed("stats")
+        .map_size(120)
+        .steppers(60)
+        .steps(150)
+        .build()
+        .expect("the config is valid");
+
+    generator.generate().expect("the map generates");
+
+    let stats = generator.get_stats();
+    let tiles = 120 * 120;
+    let land: usize = stats.islands.iter().sum();
+
+    assert_eq!(stats.seed, "stats");
+    assert_eq!(stats.biomes.iter().map(|biome| biome.tiles).sum::<usize>(), tiles);
+    assert!((stats.biomes.iter().map(|biome| biome.percentage).sum::<f32>() - 100.0).abs() < 0.01);
+    assert!((stats.land_ratio - land as f32 / tiles as f32).abs() < 1e-6);
+    assert_eq!(stats.elevation.iter().sum::<usize>(), land);
+    assert_eq!(stats.moisture.iter().sum::<usize>(), land);
+    assert!(stats.coastline > 0);
+    assert!(stats.biomes.windows(2).all(|pair| pair[0].tiles >= pair[1].tiles));
+}
+
+#[test]
+fn the_stats_are_written_as_json() {
+    let stats = MapStats::new("hand made", &hand_made_map(), &BiomeTable::default());
+    let mut json = Vec::new();
+
+    stats.to_writer(&mut json).expect("the stats are written");
+
+    let value: serde_json::Value = serde_json::from_slice(&json).expect("the stats are json");
+
+    assert_eq!(value["seed"], "hand made");
+    assert_eq!(value["islands"], serde_json::json!([16, 1]));
+    assert_eq!(value["coastline"], 24);
+    assert_eq!(value["biomes"][1]["name"], "Grassland");
+    assert_eq!(value["biomes"][1]["tiles"], 16);
+}
@@ -0,0 +1,167 @@
+use std::fs;
+use std::path::PathBuf;
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::biomes::table::{BiomeDefinition, BiomeTable};
+use whittaker_map_generator::exporters::tiled::{biome_tile_id, elevation_tile_id, moisture_tile_id};
+use whittaker_map_generator::generator::Generator;
+use whittaker_map_generator::shape::Shape;
+use whittaker_map_generator::steppers::map_position::MapPosition;
+
+// a directory of its own for every test, so they can run side by side
+fn output_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("whittaker_tiled_{}", name));
+
+    fs::create_dir_all(&dir).expect("the output directory is created");
+    dir
+}
+
+// the value of an attribute of the first element with the given tag
+fn attribute<'a>(xml: &'a str, tag: &str, name: &str) -> Option<&'a str> {
+    let element = &xml[xml.find(&format!("<{} ", tag))?..];
+    let element = &element[..element.find('>')?];
+    let value = &element[element.find(&format!(" {}=\"", name))? + name.len() + 3..];
+
+    Some(&value[..value.find('"')?])
+}
+
+// the gids of a csv layer, row by row
+fn layer(xml: &str, name: &str) -> Vec<Vec<u32>> {
+    let start = xml.find(&format!(r#"name="{}""#, name)).expect("the layer is in the map");
+    let data = &xml[start..];
+    let data = &data[data.find('\n').expect("the layer has data") + 1..];
+    let data = &data[data.find('\n').expect("the layer has data") + 1..data.find("</data>").expect("the data ends")];
+    let lines: Vec<&str> = data.trim_end().lines().collect();
+
+    // every row but the last ends in a comma, so the rows run on into each other
+    for line in &lines[..lines.len() - 1] {
+        assert!(line.ends_with(','), "the row '{}' does not end in a comma", line);
+    }
+
+    assert!(!lines[lines.len() - 1].ends_with(','));
+
+    lines
+        .iter()
+        .map(|line| line.trim_end_matches(',').split(',').map(|gid| gid.parse().expect("a gid is a number")).collect())
+        .collect()
+}
+
+#[test]
+fn the_map_and_tileset_parse_back() {
+    let mut table = BiomeTable::default();
+    let swamp = table.register(BiomeDefinition {
+        name: "Swamp".to_string(),
+        symbol: "%".to_string(),
+        colour: [90, 110, 70],
+        rule: None,
+    }).expect("the biome is registered");
+
+    table.set_biome(2, 4, swamp).expect("the cell is in the table");
+
+    for shape in Shape::ALL {
+        let mut generator = Generator::builder()
+            .seed("tiled")
+            .width(48)
+            .height(30)
+            .steppers(40)
+            .steps(60)
+            .shape(shape)
+            .biome_table(table.clone())
+            .build()
+            .expect("the config is valid");
+        let dir = output_dir(shape.get_name());
+
+        generator.generate().expect("the map generates");
+        generator
+            .output_tiled(dir.join("map.tmx").to_string_lossy().to_string(), 16)
+            .expect("the map exports");
+
+        let map = fs::read_to_string(dir.join("map.tmx")).expect("the map reads");
+        let tileset = fs::read_to_string(dir.join("map.tsx")).expect("the tileset reads");
+        let image = image::open(dir.join("map_tileset.png")).expect("the tileset image reads");
+        fs::remove_dir_all(&dir).ok();
+
+        let map_data = generator.get_map_data();
+
+        assert_eq!(attribute(&map, "map", "width"), Some("48"));
+        assert_eq!(attribute(&map, "map", "height"), Some("30"));
+        assert_eq!(attribute(&map, "tileset", "firstgid"), Some("1"));
+        assert_eq!(attribute(&map, "tileset", "source"), Some("map.tsx"));
+
+        match shape {
+            Shape::Square => {
+                assert_eq!(attribute(&map, "map", "orientation"), Some("orthogonal"));
+                assert_eq!(attribute(&map, "map", "staggeraxis"), None);
+            }
+            Shape::Hex => {
+                assert_eq!(attribute(&map, "map", "orientation"), Some("hexagonal"));
+                assert_eq!(attribute(&map, "map", "staggeraxis"), Some("y"));
+                assert_eq!(attribute(&map, "map", "staggerindex"), Some("odd"));
+                assert_eq!(attribute(&map, "map", "hexsidelength"), Some("8"));
+            }
+        }
+
+        // one tile for every built-in biome, elevation and moisture band, then the custom biomes
+        let number = |name: &str| -> u32 {
+            attribute(&tileset, "tileset", name).and_then(|value| value.parse().ok()).expect("the tileset has it")
+        };
+        let (tile_count, columns) = (number("tilecount"), number("columns"));
+
+        assert_eq!(tile_count, Biomes::ALL.len() as u32 + 4 + 6 + 1);
+        assert_eq!(tileset.matches("<tile id=").count() as u32, tile_count);
+        assert!(tileset.contains(&format!(r#"<tile id="{}">"#, biome_tile_id(swamp))));
+        assert_eq!(biome_tile_id(swamp), tile_count - 1);
+        assert_eq!((image.width(), image.height()), (columns * 16, tile_count.div_ceil(columns) * 16));
+
+        let biomes = layer(&map, "biome");
+        let elevation = layer(&map, "elevation");
+        let moisture = layer(&map, "moisture");
+
+        assert_eq!(biomes.len(), 30);
+
+        for (position, tile) in map_data.enumerate() {
+            let MapPosition { x, y } = position;
+            let (x, y) = (x as usize, y as usize);
+
+            assert_eq!(biomes[y].len(), 48);
+            // gid 0 is an empty cell, so the gids are the tile ids offset by the firstgid
+            assert_eq!(biomes[y][x], biome_tile_id(tile.tile_type) + 1);
+            assert_eq!(elevation[y][x], if tile.is_land() { elevation_tile_id(tile.elevation) + 1 } else { 0 });
+            assert_eq!(moisture[y][x], if tile.is_land() { moisture_tile_id(tile.moisture) + 1 } else { 0 });
+        }
+
+        assert!(biomes.iter().flatten().any(|gid| *gid == biome_tile_id(swamp) + 1), "no swamp was exported");
+    }
+}
+
+#[test]
+fn biome_names_are_escaped_in_the_tileset() {
+    let mut table = BiomeTable::default();
+
+    table.register(BiomeDefinition {
+        name: r#"Rock & "Roll" <deep>"#.to_string(),
+        symbol: "r".to_string(),
+        colour: [90, 80, 70],
+        rule: None,
+    }).expect("the biome is registered");
+
+    let mut generator = Generator::builder()
+        .seed("tiled")
+        .map_size(40)
+        .steppers(20)
+        .steps(40)
+        .biome_table(table)
+        .build()
+        .expect("the config is valid");
+    let dir = output_dir("escaped");
+
+    generator.generate().expect("the map generates");
+    generator
+        .output_tiled(dir.join("map.tmx").to_string_lossy().to_string(), 8)
+        .expect("the map exports");
+
+    let tileset = fs::read_to_string(dir.join("map.tsx")).expect("the tileset reads");
+    fs::remove_dir_all(&dir).ok();
+
+    assert!(tileset.contains(r#"<property name="name" value="Rock &amp; &quot;Roll&quot; &lt;deep&gt;"/>"#));
+    assert!(!tileset.contains("<deep>"));
+}
@@ -0,0 +1,143 @@
+use whittaker_map_generator::biomes::biomes::Biomes;
+use whittaker_map_generator::biomes::Biome;
+use whittaker_map_generator::distance::distance_field;
+use whittaker_map_generator::generator::Generator;
+use whittaker_map_generator::grid::Grid;
+use whittaker_map_generator::regions::Regions;
+use whittaker_map_generator::shape::Shape;
+use whittaker_map_generator::steppers::map_position::MapPosition;
+use whittaker_map_generator::steppers::ridge::Ridge;
+use whittaker_map_generator::steppers::StepperBehaviour;
+use whittaker_map_generator::topology::Topology;
+
+const SIZE: u32 = 120;
+
+// the first of a few seeds whose land reaches across the east-west seam
+fn straddling_map() -> Generator {
+    (0..20)
+        .map(|attempt| {
+            let mut generator = Generator::builder()
+                .seed(format!("seam {}", attempt))
+                .map_size(SIZE)
+                .steppers(150)
+                .steps(200)
+                .topology(Topology::Cylindrical)
+                .build()
+                .expect("the config is valid");
+
+            generator.generate().expect("the map generates");
+            generator
+        })
+        .find(|generator| {
+            let map_data = generator.get_map_data();
+
+            (0..SIZE as i32).any(|y| {
+                map_data[MapPosition { x: 0, y }].is_land() && map_data[MapPosition { x: SIZE as i32 - 1, y }].is_land()
+            })
+        })
+        .expect("some land reaches across the seam")
+}
+
+#[test]
+fn landmasses_straddle_the_seam() {
+    let generator = straddling_map();
+    let map_data = generator.get_map_data();
+    let regions = Regions::landmasses(map_data);
+
+    for y in 0..SIZE as i32 {
+        let (west, east) = (MapPosition { x: 0, y }, MapPosition { x: SIZE as i32 - 1, y });
+
+        if map_data[west].is_land() && map_data[east].is_land() {
+            assert_eq!(regions.id_at(west), regions.id_at(east), "the land at row {} is split at the seam", y);
+        }
+
+        // nothing is cut off at the edges of a map which wraps
+        assert!(map_data[west].tile_type != Biomes::Void && map_data[east].tile_type != Biomes::Void);
+    }
+}
+
+#[test]
+fn beaches_and_coasts_look_across_the_seam() {
+    let generator = straddling_map();
+    let map_data = generator.get_map_data();
+
+    for y in 0..SIZE as i32 {
+        for position in [MapPosition { x: 0, y }, MapPosition { x: SIZE as i32 - 1, y }] {
+            let tile = &map_data[position];
+
+            if !tile.is_land() {
+                continue;
+            }
+
+            let touches_sea = map_data
+                .surrounding(position)
+                .any(|neighbour| map_data[neighbour].tile_type == Biomes::SaltWater);
+
+            // a beach forms on low, dry shores, and only where the sea really is, on either side of the seam
+            assert_eq!(
+                tile.tile_type == Biomes::Beach,
+                touches_sea && tile.elevation == 1 && tile.moisture <= 2,
+                "the beach at {:?} does not match its shore",
+                position
+            );
+
+            // the edge of the map is no coast, so land away from the sea there is inland
+            if !touches_sea {
+                assert!(tile.distance_from_sea >= 2, "the land at {:?} is treated as coast", position);
+            }
+        }
+    }
+}
+
+#[test]
+fn distances_are_measured_across_the_seam() {
+    for topology in [Topology::Cylindrical, Topology::Toroidal] {
+        let mut map_data = Grid::new(10, 8, Biome::new(Biomes::Placeholder)).with_topology(topology);
+
+        map_data[MapPosition { x: 0, y: 0 }].tile_type = Biomes::SaltWater;
+
+        let distances = distance_field(&map_data, Biomes::SaltWater);
+
+        // one step west of the sea, across the seam
+        assert_eq!(distances[MapPosition { x: 9, y: 0 }], 1.0);
+        assert_eq!(distances[MapPosition { x: 8, y: 1 }], 5.0_f32.sqrt());
+
+        let north_south = if topology.wraps_y() { 1.0 } else { 7.0 };
+
+        assert_eq!(distances[MapPosition { x: 0, y: 7 }], north_south);
+    }
+}
+
+#[test]
+fn ridges_rise_evenly_across_the_seam() {
+    let mut map_data = Grid::new(10, 10, Biome::new(Biomes::Placeholder)).with_topology(Topology::Toroidal);
+
+    Ridge::create(1.0, 3).on_step(&mut map_data, MapPosition { x: 0, y: 0 }, 1, 0);
+
+    for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 1)] {
+        let position = MapPosition { x, y };
+        // the same offset from the crest, mirrored to the other side of each seam
+        let (west, north) = (MapPosition { x: (10 - x) % 10, y }, MapPosition { x, y: (10 - y) % 10 });
+
+        assert!(map_data[position].height > 0.0);
+        assert_eq!(map_data[position].height, map_data[west].height, "the slope differs across the seam at {:?}", west);
+        assert_eq!(map_data[position].height, map_data[north].height, "the slope differs across the seam at {:?}", north);
+    }
+}
+
+#[test]
+fn topologies_and_shapes_parse_from_their_names() {
+    for topology in Topology::ALL {
+        assert_eq!(topology.to_string().to_uppercase().parse::<Topology>(), Ok(topology));
+    }
+
+    for shape in Shape::ALL {
+        assert_eq!(shape.get_name().parse::<Shape>(), Ok(shape));
+    }
+
+    assert_eq!(
+        "sphere".parse::<Topology>(),
+        Err("unknown topology 'sphere', expected one of: bounded, cylindrical, toroidal".to_string())
+    );
+    assert_eq!("octagon".parse::<Shape>(), Err("unknown shape 'octagon', expected one of: square, hex".to_string()));
+}